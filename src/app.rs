@@ -1,11 +1,15 @@
 use crate::{
     clipboard::Clipboard,
     models::{
-        editor::EditorCommand, search::Search, search_result::SearchResult, SearchCondition,
-        SearchConfig,
+        editor::EditorCommand, search::Search, search_result::SearchResult, CancellationToken,
+        History, SearchCondition, SearchConfig, UndoJournal,
     },
+    parser::{parse, Aliases},
     tui,
-    ui::{self, Confirm, Help, Icon, Input, InputList, SelectCondition, Theme},
+    ui::{
+        self, Confirm, DiffPreview, Help, Icon, Input, InputList, SelectCondition, SelectHistory,
+        Theme,
+    },
 };
 use anyhow::{anyhow, Result};
 use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
@@ -15,30 +19,36 @@ use ratatui::{
     Frame,
 };
 use std::{
-    sync::{mpsc, Arc},
+    sync::{mpsc, Arc, Mutex},
     thread,
     time::Duration,
 };
 
 const CONFIRM_REPLACE_ID: &str = "confirm_replace";
+const MAX_EVENTS_PER_FRAME: usize = 1000;
 
 type TotalCount = usize;
 type CurrentCount = usize;
+type ScannedCount = usize;
+type MatchedCount = usize;
+type Generation = usize;
 
 #[derive(PartialEq, Eq)]
 enum State {
     Exit,
     Idle,
-    Searching,
+    Searching(ScannedCount, MatchedCount),
     Processing(TotalCount, CurrentCount),
 }
 
 struct Views {
     confirm: Option<ui::Confirm>,
+    diff_preview: Option<ui::DiffPreview>,
     file_preview: Option<ui::FilePreview>,
     input_list: InputList,
     search_result: Option<ui::SearchResult>,
     select_condition: Option<ui::SelectCondition>,
+    select_history: Option<ui::SelectHistory>,
     status: Option<ui::Status>,
 }
 
@@ -47,7 +57,11 @@ pub struct App<'a> {
     search: Search,
     search_result: Option<SearchResult>,
     state: State,
+    generation: Generation,
+    cancellation_token: CancellationToken,
     conditions: Vec<SearchCondition>,
+    aliases: Arc<Aliases>,
+    history: History,
     tx: mpsc::Sender<ui::Event>,
     rx: mpsc::Receiver<ui::Event>,
     views: Views,
@@ -67,27 +81,39 @@ impl<'a> App<'a> {
         stdin: Option<String>,
     ) -> Self {
         let (tx, rx) = mpsc::channel();
+        let history = History::load();
+        let aliases = Arc::new(Aliases::default());
+        let mut input_list = InputList::new(
+            conditions
+                .iter()
+                .map(|c| Input::entered(c.clone(), tx.clone()))
+                .collect(),
+            Arc::clone(&aliases),
+            tx.clone(),
+        );
+
+        input_list.set_history(history.entries());
 
         Self {
             config,
             search: Search::new(path_list.clone(), stdin),
             search_result: None,
             state: State::Idle,
-            conditions: conditions.clone(),
+            generation: 0,
+            cancellation_token: CancellationToken::default(),
+            conditions,
+            aliases,
+            history,
             tx: tx.clone(),
             rx,
             views: Views {
                 confirm: None,
+                diff_preview: None,
                 file_preview: None,
-                input_list: InputList::new(
-                    conditions
-                        .into_iter()
-                        .map(|c| Input::entered(c, tx.clone()))
-                        .collect(),
-                    tx.clone(),
-                ),
+                input_list,
                 search_result: None,
                 select_condition: None,
+                select_history: None,
                 status: Some(ui::Status::new(Some(ui::Message::Info(format!(
                     "Directory to search => {}",
                     path_list.join(", ")
@@ -102,6 +128,10 @@ impl<'a> App<'a> {
     pub fn run(&mut self, terminal: &mut tui::Tui) -> Result<()> {
         self.search_async(self.conditions.clone());
 
+        if !self.conditions.is_empty() {
+            self.record_history();
+        }
+
         while self.state != State::Exit {
             terminal.draw(|frame| self.draw(frame))?;
             self.handle_events(terminal);
@@ -114,6 +144,12 @@ impl<'a> App<'a> {
         self.views.status = Some(ui::Status::new(Some(ui::Message::Error(message))));
     }
 
+    /// Sets the saved queries and aliases used by queries entered in the TUI.
+    pub fn set_aliases(&mut self, aliases: Arc<Aliases>) {
+        self.views.input_list.set_aliases(Arc::clone(&aliases));
+        self.aliases = aliases;
+    }
+
     fn draw(&mut self, f: &mut Frame) {
         let [input_area, list_area, status_area, help_area] = Layout::vertical([
             Constraint::Length(3),
@@ -127,7 +163,7 @@ impl<'a> App<'a> {
             .input_list
             .draw(f, input_area, self.theme.clone(), self.icon.clone());
         match self.state {
-            State::Idle => {
+            State::Idle | State::Searching(_, _) if self.views.search_result.is_some() => {
                 if let Some(result) = self.views.search_result.as_mut() {
                     if let Some(file_preview) = self.views.file_preview.as_mut() {
                         let [list_area, preview_area] = Layout::horizontal([
@@ -136,20 +172,28 @@ impl<'a> App<'a> {
                         ])
                         .areas(list_area);
                         let line_no = result.selected().map(|(_, l)| l.line_no).unwrap_or(0);
-                        file_preview.draw(f, preview_area, line_no);
+                        file_preview.draw(f, preview_area, line_no, self.theme.clone());
                         result.draw(f, list_area, self.theme.clone());
                     } else {
                         result.draw(f, list_area, self.theme.clone());
                     }
-                } else {
-                    let message = Paragraph::new("Please enter search keywords")
-                        .style(self.theme.foreground_style())
-                        .block(Block::default().padding(Padding::top(2)))
-                        .centered();
-                    f.render_widget(message, list_area);
                 }
+
+                if let State::Searching(scanned @ 1.., matched) = self.state {
+                    self.views.status = Some(ui::Status::new(Some(ui::Message::Info(format!(
+                        "Searching... {} of {} files matched",
+                        matched, scanned
+                    )))));
+                }
+            }
+            State::Idle => {
+                let message = Paragraph::new("Please enter search keywords")
+                    .style(self.theme.foreground_style())
+                    .block(Block::default().padding(Padding::top(2)))
+                    .centered();
+                f.render_widget(message, list_area);
             }
-            State::Searching => {
+            State::Searching(_, _) => {
                 let p = Paragraph::new("Searching...")
                     .block(Block::default().padding(Padding::top(2)))
                     .alignment(Alignment::Center);
@@ -185,6 +229,16 @@ impl<'a> App<'a> {
             );
         }
 
+        if let Some(select_history_popup) = self.views.select_history.as_mut() {
+            let popup_area = App::centered_rect(90, 90, f.area());
+            select_history_popup.draw(f, popup_area, self.theme.clone());
+        }
+
+        if let Some(diff_preview) = self.views.diff_preview.as_mut() {
+            let popup_area = App::centered_rect(90, 90, f.area());
+            diff_preview.draw(f, popup_area, self.theme.clone());
+        }
+
         if let Some(confirm) = self.views.confirm.as_mut() {
             let popup_area = App::centered_rect(60, 60, f.area());
             confirm.draw(f, popup_area, self.theme.clone());
@@ -200,7 +254,13 @@ impl<'a> App<'a> {
     }
 
     fn handle_events(&mut self, terminal: &mut tui::Tui) {
-        if let Ok(event) = self.rx.try_recv() {
+        let events = self
+            .rx
+            .try_iter()
+            .take(MAX_EVENTS_PER_FRAME)
+            .collect::<Vec<_>>();
+
+        for event in events {
             match event {
                 ui::Event::ClickConfirmNo => {
                     self.views.confirm = None;
@@ -213,14 +273,55 @@ impl<'a> App<'a> {
                     self.replace_async();
                 }
                 ui::Event::ClickConfirmYes(_) => (),
-                ui::Event::SearchFinished(result) => {
+                ui::Event::SearchProgress(generation, _)
+                | ui::Event::FileScanned(generation, _)
+                | ui::Event::SearchFinished(generation, _)
+                | ui::Event::SearchFailed(generation, _)
+                    if generation != self.generation => {}
+                ui::Event::SearchProgress(_, file) => {
+                    if let State::Searching(scanned, matched) = self.state {
+                        let view = self.views.search_result.get_or_insert_with(|| {
+                            ui::SearchResult::new(
+                                &[],
+                                self.config.to_search_result_config(),
+                                self.tx.clone(),
+                            )
+                        });
+
+                        if view.push(&file) {
+                            self.state = State::Searching(scanned, matched + 1);
+                        }
+                    }
+                }
+                ui::Event::FileScanned(_, inc) => {
+                    if let State::Searching(scanned, matched) = self.state {
+                        self.state = State::Searching(scanned + inc, matched);
+                    }
+                }
+                ui::Event::SearchFailed(_, message) => {
+                    self.state = State::Idle;
+                    self.views.status = Some(ui::Status::new(Some(ui::Message::Error(message))));
+                }
+                ui::Event::SearchFinished(_, result) => {
                     self.state = State::Idle;
                     self.search_result = Some(result.clone());
-                    self.views.search_result = Some(ui::SearchResult::new(
+
+                    let selected = self
+                        .views
+                        .search_result
+                        .as_mut()
+                        .and_then(|view| view.selected());
+                    let mut view = ui::SearchResult::new(
                         &result.files,
                         self.config.to_search_result_config(),
                         self.tx.clone(),
-                    ));
+                    );
+
+                    if let Some((file, line)) = selected {
+                        view.select_line(&file, &line);
+                    }
+
+                    self.views.search_result = Some(view);
                     self.views.status =
                         Some(ui::Status::new(Some(ui::Message::Stat(result.stat()))));
                 }
@@ -228,6 +329,11 @@ impl<'a> App<'a> {
                     // TODO: handle error
                     self.state = State::Exit;
                 }
+                ui::Event::DeleteSearchCondition(count)
+                    if count > 0 && matches!(self.state, State::Searching(_, _)) =>
+                {
+                    self.search_async(self.views.input_list.conditions())
+                }
                 ui::Event::DeleteSearchCondition(count) if count > 0 => {
                     self.delete_last_condition()
                 }
@@ -237,31 +343,54 @@ impl<'a> App<'a> {
                         "Searching...".to_string(),
                     ))));
                     self.search_async(vec![c]);
+                    self.record_history();
+                }
+                ui::Event::StartResultSearch(_, _)
+                    if matches!(self.state, State::Searching(_, _)) =>
+                {
+                    self.views.status = Some(ui::Status::new(Some(ui::Message::Info(
+                        "Searching...".to_string(),
+                    ))));
+                    self.search_async(self.views.input_list.conditions());
+                    self.record_history();
                 }
                 ui::Event::StartResultSearch(c, index) => {
                     self.views.status = Some(ui::Status::new(Some(ui::Message::Info(
                         "Searching...".to_string(),
                     ))));
                     self.apply(c, index);
+                    self.record_history();
                 }
                 ui::Event::ReplaceSelectLine(f, l) if self.views.input_list.has_transform() => {
                     if let Some(result) = self.search_result.as_mut() {
-                        if result.reflect_on_selected_row(&f, &l).is_ok() {
-                            self.views.search_result = Some(ui::SearchResult::new(
-                                &result.files,
-                                self.config.to_search_result_config(),
-                                self.tx.clone(),
-                            ));
-                            self.views.status =
-                                Some(ui::Status::new(Some(ui::Message::Info(format!(
-                                    "Replaced {} occurrences in {}",
-                                    &l.matches().len(),
-                                    f.file_path
-                                )))));
-                        } else {
-                            self.views.status = Some(ui::Status::new(Some(ui::Message::Error(
-                                "Replace failed".to_string(),
-                            ))));
+                        match UndoJournal::dir()
+                            .and_then(|dir| UndoJournal::begin(&dir))
+                            .and_then(|journal| {
+                                result.reflect_on_selected_row(
+                                    &f,
+                                    &l,
+                                    &journal,
+                                    &self.config.decoder,
+                                )
+                            }) {
+                            Ok(_) => {
+                                self.views.search_result = Some(ui::SearchResult::new(
+                                    &result.files,
+                                    self.config.to_search_result_config(),
+                                    self.tx.clone(),
+                                ));
+                                self.views.status =
+                                    Some(ui::Status::new(Some(ui::Message::Info(format!(
+                                        "Replaced {} occurrences in {}",
+                                        &l.matches().len(),
+                                        f.file_path
+                                    )))));
+                            }
+                            Err(e) => {
+                                self.views.status = Some(ui::Status::new(Some(
+                                    ui::Message::Error(format!("Replace failed: {}", e)),
+                                )));
+                            }
                         }
                     }
                 }
@@ -280,6 +409,28 @@ impl<'a> App<'a> {
                     self.views.input_list.set_current_condition(c);
                     self.views.select_condition = None;
                 }
+                ui::Event::SelectAlias(alias) => {
+                    self.views.input_list.enter_alias(&alias);
+                    self.views.select_condition = None;
+                }
+                ui::Event::SelectQuery(query) => {
+                    self.views.select_history = None;
+
+                    match parse(&query, &self.aliases) {
+                        Ok(conditions) => {
+                            self.views.input_list.set_conditions(conditions.clone());
+                            self.views.status = Some(ui::Status::new(Some(ui::Message::Info(
+                                "Searching...".to_string(),
+                            ))));
+                            self.search_async(conditions);
+                            self.record_history();
+                        }
+                        Err(e) => {
+                            self.views.status =
+                                Some(ui::Status::new(Some(ui::Message::Error(e.to_string()))));
+                        }
+                    }
+                }
                 ui::Event::ShowMessage(msg) => {
                     self.views.status = msg;
                 }
@@ -290,32 +441,73 @@ impl<'a> App<'a> {
                 }
                 ui::Event::ChangeResultLine(f, _) => {
                     if let Some(file_preview) = self.views.file_preview.as_mut() {
-                        if !file_preview.is_same_file(f.file_path.clone()) {
-                            if let Ok(f) = ui::FilePreview::new(f.file_path) {
-                                self.views.file_preview = Some(f);
+                        if file_preview.is_same_file(f.file_path.clone()) {
+                            file_preview.set_result(&f);
+                        } else if let Ok(mut preview) = ui::FilePreview::new(
+                            f.file_path.clone(),
+                            &self.config.decoder,
+                            self.config.preview_theme.as_deref(),
+                        ) {
+                            preview.set_result(&f);
+
+                            if file_preview.is_showing_transforms() {
+                                preview.toggle_transforms();
                             }
+
+                            self.views.file_preview = Some(preview);
                         }
                     }
                 }
+                ui::Event::ConfirmReplace => {
+                    self.views.diff_preview = None;
+
+                    if let Some(result) = self.replace_target() {
+                        let stat = result.stat();
+
+                        self.views.confirm = Some(Confirm::new(
+                            CONFIRM_REPLACE_ID.to_string(),
+                            "Confirmation".to_string(),
+                            format!(
+                                "Replace {} occurrences across {} files?",
+                                stat.match_count, stat.file_count
+                            ),
+                            "Replace".to_string(),
+                            "Cancel".to_string(),
+                            self.tx.clone(),
+                        ));
+                    }
+                }
                 ui::Event::StartReplace => {
                     self.views.status = Some(ui::Status::new(Some(ui::Message::Info(
                         "Processing...".to_string(),
                     ))));
                     self.replace_async();
                 }
-                ui::Event::ReplaceFinished => {
+                ui::Event::ReplaceFailed(message) => {
+                    self.state = State::Idle;
+                    self.views.status = Some(ui::Status::new(Some(ui::Message::Error(message))));
+                }
+                ui::Event::ReplaceFinished(skipped) => {
                     self.state = State::Idle;
 
-                    if let Some(result) = self.search_result.as_mut() {
+                    if let Some(result) = self.replace_target() {
                         let stat = result.stat();
                         self.search_result = None;
                         self.views.search_result = None;
-                        self.views.input_list = InputList::new(Vec::new(), self.tx.clone());
-                        self.views.status =
-                            Some(ui::Status::new(Some(ui::Message::Info(format!(
+                        self.views.input_list =
+                            InputList::new(Vec::new(), Arc::clone(&self.aliases), self.tx.clone());
+                        self.views.status = Some(ui::Status::new(Some(if skipped.is_empty() {
+                            ui::Message::Info(format!(
                                 "Replaced {} occurrences across {} files",
                                 stat.match_count, stat.file_count
-                            )))));
+                            ))
+                        } else {
+                            ui::Message::Warn(format!(
+                                "Skipped {} files modified since the search: {}",
+                                skipped.len(),
+                                skipped.join(", ")
+                            ))
+                        })));
                     }
                 }
             }
@@ -323,7 +515,7 @@ impl<'a> App<'a> {
     }
 
     fn handle_inputs(&mut self) -> Result<()> {
-        let duration = if matches!(self.state, State::Searching) {
+        let duration = if matches!(self.state, State::Searching(_, _)) {
             Duration::from_millis(1)
         } else {
             Duration::from_millis(100)
@@ -333,9 +525,18 @@ impl<'a> App<'a> {
             if let Ok(Event::Key(key)) = event::read() {
                 if let Some(c) = self.views.select_condition.as_mut() {
                     c.handle_event(&Event::Key(key));
+                } else if let Some(h) = self.views.select_history.as_mut() {
+                    h.handle_event(&Event::Key(key));
                 } else if let Some(c) = self.views.confirm.as_mut() {
                     c.handle_event(&Event::Key(key))?;
-                } else if matches!(self.state, State::Idle)
+                } else if let Some(d) = self.views.diff_preview.as_mut() {
+                    d.handle_event(&Event::Key(key))?;
+                } else if !self
+                    .views
+                    .file_preview
+                    .as_mut()
+                    .is_some_and(|p| p.handle_event(&Event::Key(key)))
+                    && matches!(self.state, State::Idle | State::Searching(_, _))
                     && !self.views.input_list.handle_event(&Event::Key(key))
                 {
                     if let Some(r) = self.views.search_result.as_mut() {
@@ -360,20 +561,18 @@ impl<'a> App<'a> {
                             return Ok(());
                         }
 
-                        if let Some(result) = self.search_result.as_mut() {
-                            let stat = result.stat();
-
-                            self.views.confirm = Some(Confirm::new(
-                                CONFIRM_REPLACE_ID.to_string(),
-                                "Confirmation".to_string(),
-                                format!(
-                                    "Replace {} occurrences across {} files?",
-                                    stat.match_count, stat.file_count
-                                ),
-                                "Replace".to_string(),
-                                "Cancel".to_string(),
-                                self.tx.clone(),
-                            ));
+                        if let Some(result) = self.replace_target() {
+                            match result.diff(&self.config.decoder) {
+                                Ok(diff) => {
+                                    self.views.diff_preview =
+                                        Some(DiffPreview::new(diff, self.tx.clone()));
+                                }
+                                Err(e) => {
+                                    self.views.status = Some(ui::Status::new(Some(
+                                        ui::Message::Error(e.to_string()),
+                                    )));
+                                }
+                            }
                         }
                     }
                     KeyEvent {
@@ -381,6 +580,7 @@ impl<'a> App<'a> {
                     } => {
                         self.views.select_condition = Some(SelectCondition::new(
                             self.views.input_list.input_value(),
+                            &self.aliases,
                             self.tx.clone(),
                         ));
                     }
@@ -392,14 +592,27 @@ impl<'a> App<'a> {
                         if let Some(r) = self.views.search_result.as_mut() {
                             if self.views.file_preview.is_some() {
                                 self.views.file_preview = None;
-                            } else if let Some(f) = r
-                                .selected()
-                                .and_then(|(file, _)| ui::FilePreview::new(file.file_path).ok())
-                            {
-                                self.views.file_preview = Some(f);
+                            } else if let Some((file, _)) = r.selected() {
+                                if let Ok(mut f) = ui::FilePreview::new(
+                                    file.file_path.clone(),
+                                    &self.config.decoder,
+                                    self.config.preview_theme.as_deref(),
+                                ) {
+                                    f.set_result(&file);
+                                    self.views.file_preview = Some(f);
+                                }
                             }
                         }
                     }
+                    KeyEvent {
+                        code: KeyCode::Char('p'),
+                        modifiers: KeyModifiers::CONTROL,
+                        ..
+                    } => {
+                        if let Some(f) = self.views.file_preview.as_mut() {
+                            f.toggle_transforms();
+                        }
+                    }
                     KeyEvent {
                         code: KeyCode::Char('n'),
                         modifiers: KeyModifiers::CONTROL,
@@ -430,7 +643,32 @@ impl<'a> App<'a> {
                         code: KeyCode::Esc, ..
                     } => {
                         self.views.select_condition = None;
+                        self.views.select_history = None;
                         self.views.confirm = None;
+                        self.views.diff_preview = None;
+                        self.abort_search();
+                    }
+                    KeyEvent {
+                        code: KeyCode::Char('r'),
+                        modifiers: KeyModifiers::CONTROL,
+                        ..
+                    } if self.views.select_history.is_none() && self.state == State::Idle => {
+                        self.views.select_history =
+                            Some(SelectHistory::new(self.history.clone(), self.tx.clone()));
+                    }
+                    KeyEvent {
+                        code: KeyCode::Char('g'),
+                        modifiers: KeyModifiers::CONTROL,
+                        ..
+                    } => {
+                        self.abort_search();
+                    }
+                    KeyEvent {
+                        code: KeyCode::Char('z'),
+                        modifiers: KeyModifiers::CONTROL,
+                        ..
+                    } if matches!(self.state, State::Idle) => {
+                        self.undo();
                     }
                     _ => (),
                 }
@@ -441,34 +679,16 @@ impl<'a> App<'a> {
     }
 
     pub fn search_sync(&mut self) -> Result<SearchResult> {
-        let search = self.search.clone();
-        let config = self.config.clone();
-
         match self.conditions.as_slice() {
             [find, ..] if find.is_transform() => Err(anyhow!("Invalid search condition")),
-            [cond1, cond2, rest @ ..] => {
-                let rest_conditions = rest.to_vec().clone();
-                let result = search.search(config, vec![cond1.clone(), cond2.clone()]);
-                if rest_conditions.is_empty() {
-                    Ok(result)
-                } else {
-                    Ok(rest_conditions
-                        .into_iter()
-                        .fold(result, |r, c| r.apply(c, 2)))
-                }
-            }
-            [cond, rest @ ..] => {
-                let rest_conditions = rest.to_vec().clone();
-                let result = search.search(config, vec![cond.clone()]);
-                if rest_conditions.is_empty() {
-                    Ok(result)
-                } else {
-                    Ok(rest_conditions
-                        .into_iter()
-                        .fold(result, |r, c| r.apply(c, 2)))
-                }
-            }
             [] => Err(anyhow!("Invalid search condition")),
+            conditions => App::search_pipeline(
+                &self.search,
+                self.config.clone(),
+                conditions,
+                &CancellationToken::default(),
+                None,
+            ),
         }
     }
 
@@ -476,6 +696,7 @@ impl<'a> App<'a> {
         let search = self.search.clone();
         let tx = self.tx.clone();
         let config = self.config.clone();
+        let (generation, token) = self.next_generation();
 
         match conditions.as_slice() {
             [find, ..] if find.is_transform() => {
@@ -483,63 +704,131 @@ impl<'a> App<'a> {
                     "Invalid search condition".to_string(),
                 ))));
             }
-            [cond1, cond2, rest @ ..] => {
-                let rest_conditions = rest.to_vec().clone();
-                let cond1 = cond1.clone();
-                let cond2 = cond2.clone();
-
-                self.state = State::Searching;
+            [] => (),
+            _ => {
+                self.state = State::Searching(0, 0);
+                self.views.search_result = None;
 
                 std::thread::spawn(move || {
-                    let result = search.search(config, vec![cond1, cond2]);
-                    let result = if rest_conditions.is_empty() {
-                        result
-                    } else {
-                        rest_conditions
-                            .into_iter()
-                            .fold(result, |r, c| r.apply(c, 2))
-                    };
-
-                    tx.send(ui::Event::SearchFinished(result)).ok();
+                    let result = App::search_pipeline(
+                        &search,
+                        config,
+                        &conditions,
+                        &token,
+                        Some((&tx, generation)),
+                    );
+
+                    if !token.is_cancelled() {
+                        match result {
+                            Ok(result) => tx.send(ui::Event::SearchFinished(generation, result)),
+                            Err(e) => tx.send(ui::Event::SearchFailed(generation, e.to_string())),
+                        }
+                        .ok();
+                    }
                 });
             }
-            [cond, rest @ ..] => {
-                let rest_conditions = rest.to_vec().clone();
-                let cond = cond.clone();
+        }
+    }
 
-                self.state = State::Searching;
+    /// Searches files with the first condition after any file filters, and a following line filter if any,
+    /// then applies the remaining conditions to the result. Files are selected by every file filter in the query.
+    /// With `progress`, each file is also sent as soon as it has been searched.
+    fn search_pipeline(
+        search: &Search,
+        config: SearchConfig,
+        conditions: &[SearchCondition],
+        token: &CancellationToken,
+        progress: Option<(&mpsc::Sender<ui::Event>, Generation)>,
+    ) -> Result<SearchResult> {
+        let file_filters = conditions.iter().take_while(|c| c.is_file_filter()).count();
+        let (search_conditions, rest_conditions) = match &conditions[file_filters..] {
+            [cond1, cond2, rest @ ..] if cond2.is_line_filter() => {
+                (vec![cond1.clone(), cond2.clone()], rest.to_vec())
+            }
+            [cond, rest @ ..] => (vec![cond.clone()], rest.to_vec()),
+            [] => (Vec::new(), Vec::new()),
+        };
+        let search_conditions = conditions[..file_filters]
+            .iter()
+            .cloned()
+            .chain(search_conditions)
+            .collect::<Vec<_>>();
+        let config = SearchConfig {
+            file_filters: conditions
+                .iter()
+                .filter(|c| c.is_file_filter())
+                .cloned()
+                .collect(),
+            ..config
+        };
+        let start_index = search_conditions.len() + 1;
+        let apply = |result: SearchResult| {
+            rest_conditions
+                .iter()
+                .enumerate()
+                .fold(result, |r, (i, c)| r.apply(c.clone(), start_index + i))
+        };
 
-                std::thread::spawn(move || {
-                    let result = search.search(config, vec![cond]);
-                    let result = if rest_conditions.is_empty() {
-                        result
-                    } else {
-                        rest_conditions
-                            .into_iter()
-                            .fold(result, |r, c| r.apply(c, 2))
-                    };
+        // The remaining conditions are applied to each file as it is streamed, and the results are merged.
+        let parts = Mutex::new(Vec::new());
+        let result =
+            search.search_with_progress(config, search_conditions.clone(), token, |file| {
+                if let Some((tx, generation)) = progress {
+                    tx.send(ui::Event::FileScanned(generation, 1)).ok();
+
+                    if let Some(file) = file {
+                        let part = apply(SearchResult::new(
+                            vec![file.clone()],
+                            search_conditions.clone(),
+                        ));
 
-                    tx.send(ui::Event::SearchFinished(result)).ok();
-                });
-            }
-            [] => (),
-        }
+                        part.files.iter().for_each(|f| {
+                            tx.send(ui::Event::SearchProgress(generation, Arc::clone(f)))
+                                .ok();
+                        });
+                        parts.lock().unwrap().push(part);
+                    }
+                }
+            })?;
+        let parts = parts.into_inner().unwrap();
+
+        Ok(if !parts.is_empty() && parts.len() == result.files.len() {
+            result.merge(parts)
+        } else {
+            apply(result)
+        })
+    }
+
+    /// The lines that Ctrl-e replaces: the marked lines, or every line if none are marked.
+    fn replace_target(&self) -> Option<SearchResult> {
+        let marks = self
+            .views
+            .search_result
+            .as_ref()
+            .map(|r| r.marks())
+            .filter(|marks| !marks.is_empty());
+
+        self.search_result.as_ref().map(|result| match marks {
+            Some(marks) => result.retain_marked(marks),
+            None => result.clone(),
+        })
     }
 
     fn replace_async(&mut self) {
         let tx = self.tx.clone();
+        let decoder = self.config.decoder;
 
-        if let Some(result) = self.search_result.clone() {
+        if let Some(result) = self.replace_target() {
             self.state = State::Processing(result.stat().file_count, 0);
             std::thread::spawn(move || {
-                std::thread::spawn(move || match result.reflect(tx.clone()) {
-                    Ok(_) => tx.send(ui::Event::ReplaceFinished).ok(),
-                    Err(e) => tx
-                        .send(ui::Event::ShowMessage(Some(ui::Status::new(Some(
-                            ui::Message::Error(e.to_string()),
-                        )))))
-                        .ok(),
-                });
+                match UndoJournal::dir()
+                    .and_then(|dir| UndoJournal::begin(&dir))
+                    .and_then(|journal| result.reflect(tx.clone(), &journal, &decoder))
+                {
+                    Ok(skipped) => tx.send(ui::Event::ReplaceFinished(skipped)),
+                    Err(e) => tx.send(ui::Event::ReplaceFailed(e.to_string())),
+                }
+                .ok();
             });
         };
     }
@@ -548,15 +837,63 @@ impl<'a> App<'a> {
         let tx = self.tx.clone();
 
         if let Some(result) = self.search_result.clone() {
-            self.state = State::Searching;
+            let (generation, _) = self.next_generation();
+
+            self.state = State::Searching(0, 0);
             std::thread::spawn(move || {
-                tx.send(ui::Event::SearchFinished(result.apply(matcher, index)))
-                    .ok();
+                tx.send(ui::Event::SearchFinished(
+                    generation,
+                    result.apply(matcher, index),
+                ))
+                .ok();
             });
         };
     }
 
+    /// Cancels the running search and makes any of its pending events stale.
+    fn next_generation(&mut self) -> (Generation, CancellationToken) {
+        self.cancellation_token.cancel();
+        self.cancellation_token = CancellationToken::default();
+        self.generation += 1;
+
+        (self.generation, self.cancellation_token.clone())
+    }
+
+    fn abort_search(&mut self) {
+        if matches!(self.state, State::Searching(_, _)) {
+            self.next_generation();
+            self.state = State::Idle;
+            self.views.status = Some(ui::Status::new(Some(ui::Message::Warn(
+                "Search aborted".to_string(),
+            ))));
+        }
+    }
+
+    /// Saves the current query to the history and makes it available to recall.
+    fn record_history(&mut self) {
+        if self.history.push(&self.views.input_list.query()).is_ok() {
+            self.views.input_list.set_history(self.history.entries());
+        }
+    }
+
+    /// Restores the files changed by the last replacement and searches them again.
+    fn undo(&mut self) {
+        match UndoJournal::dir().and_then(|dir| UndoJournal::undo(&dir)) {
+            Ok(files) => {
+                self.search_async(self.views.input_list.conditions());
+                self.views.status = Some(ui::Status::new(Some(ui::Message::Info(format!(
+                    "Restored {} files",
+                    files.len()
+                )))));
+            }
+            Err(e) => {
+                self.views.status = Some(ui::Status::new(Some(ui::Message::Error(e.to_string()))));
+            }
+        }
+    }
+
     fn delete_first_condition(&mut self) {
+        self.next_generation();
         self.state = State::Idle;
         self.search_result = None;
         self.views.search_result = None;
@@ -566,10 +903,15 @@ impl<'a> App<'a> {
         let tx = self.tx.clone();
 
         if let Some(mut result) = self.search_result.clone() {
-            self.state = State::Searching;
+            let (generation, _) = self.next_generation();
+
+            self.state = State::Searching(0, 0);
             std::thread::spawn(move || {
-                tx.send(ui::Event::SearchFinished(result.delete_last_condition()))
-                    .ok();
+                tx.send(ui::Event::SearchFinished(
+                    generation,
+                    result.delete_last_condition(),
+                ))
+                .ok();
             });
         };
     }
@@ -609,3 +951,62 @@ impl<'a> App<'a> {
         };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+    use rstest::rstest;
+    use std::ops::Range;
+
+    #[rstest]
+    #[case(vec![SearchCondition::Exact("foo".to_string()), SearchCondition::Exact("bar".to_string())],
+           vec![(1, 0..3), (2, 4..7)])]
+    #[case(vec![SearchCondition::Exact("foo".to_string()),
+                SearchCondition::Exact("bar".to_string()),
+                SearchCondition::Exact("baz".to_string())],
+           vec![(1, 0..3), (2, 4..7), (3, 8..11)])]
+    #[case(vec![SearchCondition::Exact("foo".to_string()),
+                SearchCondition::LineContains("baz".to_string()),
+                SearchCondition::Exact("bar".to_string())],
+           vec![(1, 0..3), (3, 4..7)])]
+    fn test_search_pipeline(
+        #[case] conditions: Vec<SearchCondition>,
+        #[case] expected: Vec<(usize, Range<usize>)>,
+    ) {
+        let search = Search::new(Vec::new(), Some("foo bar baz\nqux\n".to_string()));
+        let (tx, _rx) = mpsc::channel();
+
+        // Streaming applies the remaining conditions file by file, which has to give the same result.
+        for progress in [None, Some((&tx, 0))] {
+            let result = App::search_pipeline(
+                &search,
+                SearchConfig::default(),
+                &conditions,
+                &CancellationToken::default(),
+                progress,
+            )
+            .unwrap();
+
+            assert_eq!(
+                result
+                    .files
+                    .iter()
+                    .flat_map(
+                        |file| file
+                            .lines
+                            .iter()
+                            .filter_map(|line| line.line())
+                            .flat_map(|line| line
+                                .matches()
+                                .iter()
+                                .map(|m| (m.index(), m.range()))
+                                .collect::<Vec<_>>())
+                    )
+                    .collect::<Vec<_>>(),
+                expected
+            );
+            assert_eq!(result.to_conditions_string(), conditions.iter().join(" | "));
+        }
+    }
+}
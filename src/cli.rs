@@ -1,25 +1,37 @@
 use crate::{
     app::App,
-    models::{Editor, EditorCommand, SearchCondition, SearchConfig},
-    parser::parse,
+    models::{
+        config::parse_filesize,
+        config_file::{ConfigFiles, Setting, Source},
+        decoder::Skip,
+        grep_input, Decoder, Editor, EditorCommand, History, InputFormat, SearchCondition,
+        SearchConfig, SearchResult, UndoJournal, WalkFilter,
+    },
+    parser::{parse, Aliases, ParseError},
     tui,
-    ui::{self, CharIcon, Dark, FontIcon},
+    ui::{self, CharIcon, ColorSupport, CustomTheme, FontIcon, Icon},
 };
 use anyhow::{anyhow, Result};
 use clap::{Parser, ValueEnum};
+use colored::Colorize;
 use indicatif::ProgressBar;
+use itertools::Itertools;
 use std::{
     env,
-    io::{self, BufWriter, Read, Write},
+    io::{self, BufWriter, IsTerminal, Read, Write},
     sync::{mpsc, Arc},
 };
-use strum::Display;
 
-#[derive(Clone, Display, ValueEnum)]
-enum Theme {
-    Dark,
-    Light,
-}
+const COPY_COMMAND: &str = "IGR_COPY_COMMAND";
+const CUSTOM_COMMAND: &str = "IGR_CUSTOM_COMMAND";
+const EXCLUDE_PATH: &str = "IGR_EXCLUDE_PATH";
+const PREVIEW_THEME: &str = "IGR_PREVIEW_THEME";
+const EDITOR_ENV: &[&str] = &[
+    "IGR_URL_OPEN_COMMAND",
+    "IGR_GITHUB_USER_NAME",
+    "IGR_GITHUB_REPOSITORY_NAME",
+    "IGR_GITHUB_BRANCH_NAME",
+];
 
 #[derive(Parser)]
 #[command(name = "igr")]
@@ -43,12 +55,12 @@ pub struct Cli {
     context: Option<usize>,
 
     /// Custom command used to open selected line. e.g.: --custom_command "code -g {file_path}:{line_no}
-    #[arg(long, env = "IGR_CUSTOM_COMMAND")]
+    #[arg(long, env = CUSTOM_COMMAND)]
     custom_command: Option<String>,
 
-    /// The string used to separate
-    #[arg(long, default_value_t = String::from("--"))]
-    context_separator: String,
+    /// The string used to separate [default: --]
+    #[arg(long)]
+    context_separator: Option<String>,
 
     /// Only print the count of individual match lines for each file
     #[arg(short, long)]
@@ -58,34 +70,90 @@ pub struct Cli {
     #[arg(long)]
     count_matches: bool,
 
+    /// Print the replacements as a unified diff instead of writing them.
+    #[arg(long)]
+    diff: bool,
+
     /// Disable tui.
     #[arg(short, long)]
     disable_tui: bool,
 
-    /// Text editor used to open selected line.
-    #[arg(long, default_value_t = Editor::Vim )]
-    editor: Editor,
+    /// Text editor used to open selected line [default: vim]
+    #[arg(long)]
+    editor: Option<Editor>,
+
+    /// The encoding of files without a BOM, e.g. `latin1` or `shift_jis` [default: utf-8]
+    #[arg(short = 'E', long)]
+    encoding: Option<String>,
 
     /// Search hidden files and directory.
-    #[arg(short = '.', long)]
+    #[arg(short = '.', long, overrides_with = "no_hidden")]
     hidden: bool,
 
+    /// Don't search hidden files and directory, even if a config file enables it.
+    #[arg(long, overrides_with = "hidden")]
+    no_hidden: bool,
+
     /// Hide Help.
-    #[arg(long)]
+    #[arg(long, overrides_with = "no_hide_help")]
     hide_help: bool,
 
+    /// Show Help, even if a config file hides it.
+    #[arg(long, overrides_with = "hide_help")]
+    no_hide_help: bool,
+
+    /// Search binary files as if they were text.
+    #[arg(short = 'a', long)]
+    text: bool,
+
+    /// How to read piped input: grep or vimgrep style `path:line:text`, `rg --json`, or plain text to search.
+    #[arg(long, value_enum, default_value_t = InputFormat::Auto)]
+    input_format: InputFormat,
+
     /// If specified, it excludes files or directories matching the given filename pattern from the search.
-    #[arg(long, env = "IGR_EXCLUDE_PATH")]
+    #[arg(long, env = EXCLUDE_PATH)]
     exclude_path: Option<String>,
 
+    /// Include or exclude files and directories matching the glob, prefixed with `!` to exclude. Can be repeated.
+    #[arg(short, long)]
+    glob: Vec<String>,
+
+    /// Like --glob, but case insensitive. Can be repeated.
+    #[arg(long)]
+    iglob: Vec<String>,
+
+    /// Only search files of the given type, e.g. `rust`. Can be repeated.
+    #[arg(short = 't', long = "type")]
+    types: Vec<String>,
+
+    /// Do not search files of the given type. Can be repeated.
+    #[arg(short = 'T', long = "type-not")]
+    types_not: Vec<String>,
+
+    /// Print the supported file types and their globs.
+    #[arg(long)]
+    type_list: bool,
+
     /// The maximum depth to recurse.
     #[arg(long)]
     max_depth: Option<usize>,
 
-    /// Don't respect .gitignore files.
+    /// Skip files larger than the given size, with an optional K, M or G suffix, e.g. `50M`.
+    #[arg(long, value_parser = parse_filesize)]
+    max_filesize: Option<u64>,
+
+    /// Read files through memory maps, which can be faster for large files.
     #[arg(long)]
+    mmap: bool,
+
+    /// Don't respect .gitignore files.
+    #[arg(long, overrides_with = "git_ignore")]
     no_git_ignore: bool,
 
+    /// Respect .gitignore files, even if a config file disables it.
+    #[arg(long, overrides_with = "no_git_ignore")]
+    git_ignore: bool,
+
     /// Never print the file path with the matched lines.
     #[arg(short = 'N', long)]
     no_file_name: bool,
@@ -99,9 +167,17 @@ pub struct Cli {
     no_color: bool,
 
     /// Not display icons.
-    #[arg(long)]
+    #[arg(long, overrides_with = "icon")]
     no_icon: bool,
 
+    /// Display icons, even if a config file disables them.
+    #[arg(long, overrides_with = "no_icon")]
+    icon: bool,
+
+    /// The syntax highlighting theme of the file preview. Themes in `~/.config/igr/themes` can also be used.
+    #[arg(long, env = PREVIEW_THEME)]
+    preview_theme: Option<String>,
+
     /// Perform replacements if disable_tui is true.
     #[arg(short, long)]
     replace: bool,
@@ -110,18 +186,34 @@ pub struct Cli {
     #[arg(long)]
     threads: Option<usize>,
 
-    /// Specify a theme.
-    #[arg(long, value_enum, default_value_t = Theme::Dark)]
-    theme: Theme,
+    /// Specify a theme, `dark`, `light`, or the name of a `.toml` file in `~/.config/igr/themes` or its path [default: dark]
+    #[arg(long)]
+    theme: Option<String>,
 
     /// Do not output matched lines. instead, exit with status 0 when there is a match and with non-zero status when there isn’t.
     #[arg(short, long)]
     quiet: bool,
 
+    /// Restore the files changed by the last replacement.
+    #[arg(long)]
+    undo: bool,
+
+    /// Print the effective settings merged from the command line, environment and config files.
+    #[arg(long)]
+    print_config: bool,
+
+    /// Print the query history, oldest first.
+    #[arg(long)]
+    history: bool,
+
     /// Specifies whether all matched results are returned, including row and column numbers.
     #[arg(long)]
     vimgrep: bool,
 
+    /// Print results as JSON Lines, one record per file begin, match, context and end.
+    #[arg(long)]
+    json: bool,
+
     /// Searches for specified files and directories
     #[arg(short, long)]
     path: Option<Vec<String>>,
@@ -129,6 +221,27 @@ pub struct Cli {
     query: Option<String>,
 }
 
+/// Settings merged from the command line, environment variables and config files, in that order of precedence.
+struct Settings {
+    after_context: Option<Setting<usize>>,
+    before_context: Option<Setting<usize>>,
+    context_separator: Setting<String>,
+    copy_command: Option<Setting<String>>,
+    custom_command: Option<Setting<String>>,
+    editor: Setting<Editor>,
+    editor_env: Vec<(&'static str, Setting<String>)>,
+    encoding: Option<Setting<String>>,
+    exclude_path: Option<Setting<String>>,
+    hidden: Setting<bool>,
+    hide_help: Setting<bool>,
+    max_depth: Option<Setting<usize>>,
+    max_filesize: Option<Setting<u64>>,
+    no_git_ignore: Setting<bool>,
+    no_icon: Setting<bool>,
+    preview_theme: Option<Setting<String>>,
+    theme: Setting<String>,
+}
+
 impl Cli {
     pub fn run(&self) -> Result<()> {
         if self.no_color {
@@ -142,109 +255,152 @@ impl Cli {
             );
         }
 
+        if self.history {
+            History::load()
+                .entries()
+                .iter()
+                .for_each(|query| println!("{}", query));
+            return Ok(());
+        }
+
+        if self.type_list {
+            WalkFilter::type_list()
+                .iter()
+                .for_each(|t| println!("{}", t));
+            return Ok(());
+        }
+
+        if self.undo {
+            UndoJournal::undo(&UndoJournal::dir()?)?
+                .iter()
+                .for_each(|path| println!("{}", path));
+            return Ok(());
+        }
+
+        let files = ConfigFiles::load(&env::current_dir()?)?;
+        let settings = self.settings(&files)?;
+
+        if self.print_config {
+            Cli::print_config(&settings, &files);
+            return Ok(());
+        }
+
+        let aliases = Arc::new(Aliases::new(files.queries(), files.aliases())?);
+
+        if let (Err(_), Some(copy_command)) = (env::var(COPY_COMMAND), &settings.copy_command) {
+            env::set_var(COPY_COMMAND, &copy_command.value);
+        }
+
+        for (name, setting) in &settings.editor_env {
+            if env::var(name).is_err() {
+                env::set_var(name, &setting.value);
+            }
+        }
+
         let path_list = match &self.path {
             Some(p) => p.clone(),
             None => vec![".".to_string()],
         };
 
-        let stdin = if self.is_cli() {
+        let stdin = if self.path.is_none() && !io::stdin().is_terminal() {
             let mut buffer = String::new();
-            io::stdin().read_to_string(&mut buffer).ok().map(|_| buffer)
+            io::stdin()
+                .read_to_string(&mut buffer)
+                .ok()
+                .map(|_| buffer)
+                .filter(|buffer| !buffer.is_empty())
         } else {
             None
         };
 
-        let has_stdin = stdin.is_some();
-        let conditions: Result<Vec<SearchCondition>> =
-            self.query.as_ref().map(|s| parse(s)).unwrap_or(Ok(vec![]));
-        let mut app = match conditions {
-            Ok(c) => App::new(
-                SearchConfig {
-                    after_context: self.after_context.or(self.context),
-                    before_context: self.before_context.or(self.context),
-                    context_separator: self.context_separator.clone(),
-                    exclude_path: self.exclude_path.clone(),
-                    hide_help: self.hide_help,
-                    no_git_exclude: self.no_git_ignore,
-                    no_git_ignore: self.no_git_ignore,
-                    no_file_name: self.no_file_name,
-                    no_line_no: self.no_line_no,
-                    hidden: self.hidden,
-                    max_depth: self.max_depth,
-                    vimgrep: self.vimgrep,
-                },
-                path_list,
-                c,
-                Arc::new(Dark),
-                if self.no_icon {
-                    Arc::new(CharIcon)
-                } else {
-                    Arc::new(FontIcon)
-                },
-                EditorCommand::new(self.editor.clone()),
-                stdin,
-            ),
-            Err(e) => {
-                let mut app = App::new(
-                    SearchConfig {
-                        after_context: self.after_context.or(self.context),
-                        before_context: self.before_context.or(self.context),
-                        context_separator: self.context_separator.clone(),
-                        hide_help: self.hide_help,
-                        exclude_path: self.exclude_path.clone(),
-                        no_git_exclude: self.no_git_ignore,
-                        no_git_ignore: self.no_git_ignore,
-                        no_file_name: self.no_file_name,
-                        no_line_no: self.no_line_no,
-                        hidden: self.hidden,
-                        max_depth: self.max_depth,
-                        vimgrep: self.vimgrep,
-                    },
-                    path_list,
-                    Vec::new(),
-                    Arc::new(Dark),
-                    if self.no_icon {
-                        Arc::new(CharIcon)
-                    } else {
-                        Arc::new(FontIcon)
-                    },
-                    EditorCommand::new(self.editor.clone()),
-                    stdin,
-                );
-
-                app.set_error(e.to_string());
-                app
+        // Grep output refers to files, so it can be browsed and replaced in the TUI.
+        let has_stdin = stdin
+            .as_deref()
+            .is_some_and(|stdin| grep_input::parse(stdin, self.input_format).is_none());
+        let conditions: Result<Vec<SearchCondition>> = self
+            .query
+            .as_ref()
+            .map(|s| parse(s, &aliases))
+            .unwrap_or(Ok(vec![]));
+
+        if self.is_cli() || has_stdin {
+            if let (Err(e), Some(query)) = (&conditions, &self.query) {
+                return Err(match e.downcast_ref::<ParseError>() {
+                    Some(e) => anyhow!(e.report(query)),
+                    None => anyhow!(e.to_string()),
+                });
             }
+        }
+        let theme: Arc<dyn ui::Theme> = Arc::new(
+            CustomTheme::load(&settings.theme.value, ColorSupport::detect())
+                .map_err(|e| anyhow!("Invalid theme in {}: {}", settings.theme.source, e))?,
+        );
+        let icon: Arc<dyn Icon> = if settings.no_icon.value {
+            Arc::new(CharIcon)
+        } else {
+            Arc::new(FontIcon)
         };
+        let (conditions, error) = match conditions {
+            Ok(c) => (c, None),
+            Err(e) => (Vec::new(), Some(e)),
+        };
+        let config = self.search_config(&settings)?;
+        let decoder = config.decoder;
+
+        WalkFilter::new(&config)?;
+
+        let mut app = App::new(
+            config,
+            path_list,
+            conditions,
+            theme,
+            icon,
+            EditorCommand::new(settings.editor.value.clone()),
+            stdin,
+        );
+
+        app.set_aliases(aliases);
+
+        if let Some(e) = error {
+            app.set_error(e.to_string());
+        }
 
         if self.is_cli() || has_stdin {
             let result = app.search_sync()?;
+
+            if !self.quiet {
+                Cli::print_skipped(&result);
+            }
+
             let stdout = io::stdout();
             let handle = stdout.lock();
             let mut writer = BufWriter::new(handle);
 
-            if self.replace {
+            if self.diff {
+                writer.write_all(result.diff(&decoder)?.as_bytes())?;
+            } else if self.replace {
                 if result.files.is_empty() {
                     return Err(anyhow!("No match found"));
                 } else if self.quiet {
                     let (tx, _) = mpsc::channel();
-                    let handle = std::thread::spawn(move || result.reflect(tx));
+                    let journal = UndoJournal::begin(&UndoJournal::dir()?)?;
+                    let handle = std::thread::spawn(move || result.reflect(tx, &journal, &decoder));
 
-                    handle.join().unwrap()?;
-                    return Ok(());
+                    return Cli::check_skipped(handle.join().unwrap()?);
                 } else {
                     let pb = ProgressBar::new(result.stat().match_count as u64);
                     let (tx, rx) = mpsc::channel();
-                    let handle = std::thread::spawn(move || result.reflect(tx));
+                    let journal = UndoJournal::begin(&UndoJournal::dir()?)?;
+                    let handle = std::thread::spawn(move || result.reflect(tx, &journal, &decoder));
 
                     while !handle.is_finished() {
                         if let Ok(ui::Event::Progress(inc)) = rx.try_recv() {
                             pb.inc(inc as u64);
                         }
                     }
-                    handle.join().unwrap()?;
+                    let skipped = handle.join().unwrap()?;
                     pb.finish_with_message("done");
-                    return Ok(());
+                    return Cli::check_skipped(skipped);
                 }
             } else if self.quiet {
                 if result.files.is_empty() {
@@ -252,6 +408,20 @@ impl Cli {
                 } else {
                     return Ok(());
                 }
+            } else if self.json {
+                for file in &result.files {
+                    write!(writer, "{}", file.display_json())?;
+                }
+
+                let stat = result.stat();
+                writeln!(
+                    writer,
+                    "{}",
+                    serde_json::json!({
+                        "type": "summary",
+                        "data": { "stats": { "files": stat.file_count, "matches": stat.match_count } }
+                    })
+                )?;
             } else if self.vimgrep {
                 writer.write_all(
                     result
@@ -268,7 +438,7 @@ impl Cli {
                     format!(
                         "{}\n",
                         result.files.into_iter().fold(0, |sum, a| {
-                            sum + a.lines.into_iter().filter(|l| l.is_line()).count()
+                            sum + a.lines.iter().filter(|l| l.is_line()).count()
                         })
                     )
                     .as_bytes(),
@@ -280,7 +450,7 @@ impl Cli {
                         result.files.into_iter().fold(0, |sum, a| {
                             sum + a
                                 .lines
-                                .into_iter()
+                                .iter()
                                 .flat_map(|l| l.line().map(|l| l.count_matches()))
                                 .sum::<usize>()
                         })
@@ -295,13 +465,237 @@ impl Cli {
             return Ok(());
         }
 
+        if let Some(theme) = &settings.preview_theme {
+            ui::syntax::theme(&theme.value)?;
+        }
+
         let mut terminal = tui::init()?;
         let app_result = app.run(&mut terminal);
         tui::restore(terminal)?;
-        app_result.map_err(anyhow::Error::from)
+        app_result
+    }
+
+    fn settings(&self, files: &ConfigFiles) -> Result<Settings> {
+        let flag = |on: bool, off: bool, f: fn(&crate::models::ConfigFile) -> Option<bool>| {
+            if on || off {
+                Setting::new(on, Source::CommandLine)
+            } else {
+                files
+                    .get(f)
+                    .unwrap_or_else(|| Setting::new(false, Source::Default))
+            }
+        };
+        let custom_command = Setting::from_cli(self.custom_command.clone(), Some(CUSTOM_COMMAND))
+            .or_else(|| files.get(|c| c.custom_command.clone()));
+        let editor = match (&self.editor, &custom_command) {
+            (Some(editor), _) => Setting::new(editor.clone(), Source::CommandLine),
+            (None, Some(command)) => command.clone().map(Editor::Custom),
+            (None, None) => match files.get(|c| c.editor.clone()) {
+                Some(editor) => Setting::new(
+                    Editor::from_str(&editor.value, true).map_err(|_| {
+                        anyhow!("Invalid editor in {}: {}", editor.source, editor.value)
+                    })?,
+                    editor.source,
+                ),
+                None => Setting::new(Editor::default(), Source::Default),
+            },
+        };
+        let editor_env = EDITOR_ENV
+            .iter()
+            .zip([
+                files.get(|c| c.url_open_command.clone()),
+                files.get(|c| c.github_user_name.clone()),
+                files.get(|c| c.github_repository_name.clone()),
+                files.get(|c| c.github_branch_name.clone()),
+            ])
+            .filter_map(|(name, file)| {
+                Setting::from_cli(env::var(name).ok(), Some(name))
+                    .or(file)
+                    .map(|setting| (*name, setting))
+            })
+            .collect_vec();
+        let max_filesize = match Setting::from_cli(self.max_filesize, None) {
+            Some(max_filesize) => Some(max_filesize),
+            None => files
+                .get(|c| c.max_filesize.clone())
+                .map(|size| {
+                    parse_filesize(&size.value)
+                        .map(|value| Setting::new(value, size.source.clone()))
+                        .map_err(|e| anyhow!("Invalid max_filesize in {}: {}", size.source, e))
+                })
+                .transpose()?,
+        };
+
+        Ok(Settings {
+            after_context: Setting::from_cli(self.after_context.or(self.context), None)
+                .or_else(|| files.get(|c| c.after_context.or(c.context))),
+            before_context: Setting::from_cli(self.before_context.or(self.context), None)
+                .or_else(|| files.get(|c| c.before_context.or(c.context))),
+            context_separator: Setting::from_cli(self.context_separator.clone(), None)
+                .or_else(|| files.get(|c| c.context_separator.clone()))
+                .unwrap_or_else(|| Setting::new("--".to_string(), Source::Default)),
+            copy_command: Setting::from_cli(env::var(COPY_COMMAND).ok(), Some(COPY_COMMAND))
+                .or_else(|| files.get(|c| c.copy_command.clone())),
+            custom_command,
+            editor,
+            editor_env,
+            encoding: Setting::from_cli(self.encoding.clone(), None)
+                .or_else(|| files.get(|c| c.encoding.clone())),
+            exclude_path: Setting::from_cli(self.exclude_path.clone(), Some(EXCLUDE_PATH))
+                .or_else(|| files.get(|c| c.exclude_path.clone())),
+            hidden: flag(self.hidden, self.no_hidden, |c| c.hidden),
+            hide_help: flag(self.hide_help, self.no_hide_help, |c| c.hide_help),
+            max_depth: Setting::from_cli(self.max_depth, None)
+                .or_else(|| files.get(|c| c.max_depth)),
+            max_filesize,
+            no_git_ignore: flag(self.no_git_ignore, self.git_ignore, |c| c.no_git_ignore),
+            no_icon: flag(self.no_icon, self.icon, |c| c.no_icon),
+            preview_theme: Setting::from_cli(self.preview_theme.clone(), Some(PREVIEW_THEME))
+                .or_else(|| files.get(|c| c.preview_theme.clone())),
+            theme: Setting::from_cli(self.theme.clone(), None)
+                .or_else(|| files.get(|c| c.theme.clone()))
+                .unwrap_or_else(|| Setting::new("dark".to_string(), Source::Default)),
+        })
+    }
+
+    fn search_config(&self, settings: &Settings) -> Result<SearchConfig> {
+        let decoder = match &settings.encoding {
+            Some(encoding) => Decoder::new(Some(&encoding.value), self.text)
+                .map_err(|e| anyhow!("Invalid encoding in {}: {}", encoding.source, e))?,
+            None => Decoder::new(None, self.text)?,
+        };
+
+        Ok(SearchConfig {
+            after_context: settings.after_context.as_ref().map(|s| s.value),
+            before_context: settings.before_context.as_ref().map(|s| s.value),
+            context_separator: settings.context_separator.value.clone(),
+            decoder,
+            exclude_path: settings.exclude_path.as_ref().map(|s| s.value.clone()),
+            file_filters: Vec::new(),
+            globs: self.glob.clone(),
+            iglobs: self.iglob.clone(),
+            hide_help: settings.hide_help.value,
+            no_git_exclude: settings.no_git_ignore.value,
+            no_git_ignore: settings.no_git_ignore.value,
+            no_file_name: self.no_file_name,
+            no_line_no: self.no_line_no,
+            hidden: settings.hidden.value,
+            input_format: self.input_format,
+            max_depth: settings.max_depth.as_ref().map(|s| s.value),
+            max_filesize: settings.max_filesize.as_ref().map(|s| s.value),
+            mmap: self.mmap,
+            preview_theme: settings.preview_theme.as_ref().map(|s| s.value.clone()),
+            types: self.types.clone(),
+            types_not: self.types_not.clone(),
+            vimgrep: self.vimgrep,
+        })
+    }
+
+    fn print_config(settings: &Settings, files: &ConfigFiles) {
+        let string = |s: &Setting<String>| (format!("{:?}", s.value), s.source.clone());
+        let number = |s: &Setting<usize>| (s.value.to_string(), s.source.clone());
+        let flag = |s: &Setting<bool>| (s.value.to_string(), s.source.clone());
+        let rows = [
+            ("after_context", settings.after_context.as_ref().map(number)),
+            (
+                "before_context",
+                settings.before_context.as_ref().map(number),
+            ),
+            (
+                "context_separator",
+                Some(string(&settings.context_separator)),
+            ),
+            ("copy_command", settings.copy_command.as_ref().map(string)),
+            (
+                "custom_command",
+                settings.custom_command.as_ref().map(string),
+            ),
+            (
+                "editor",
+                Some((
+                    format!("{:?}", settings.editor.value.to_string()),
+                    settings.editor.source.clone(),
+                )),
+            ),
+            ("encoding", settings.encoding.as_ref().map(string)),
+            ("exclude_path", settings.exclude_path.as_ref().map(string)),
+            ("hidden", Some(flag(&settings.hidden))),
+            ("hide_help", Some(flag(&settings.hide_help))),
+            ("max_depth", settings.max_depth.as_ref().map(number)),
+            (
+                "max_filesize",
+                settings
+                    .max_filesize
+                    .as_ref()
+                    .map(|s| (s.value.to_string(), s.source.clone())),
+            ),
+            ("no_git_ignore", Some(flag(&settings.no_git_ignore))),
+            ("no_icon", Some(flag(&settings.no_icon))),
+            ("preview_theme", settings.preview_theme.as_ref().map(string)),
+            ("theme", Some(string(&settings.theme))),
+        ]
+        .into_iter()
+        .chain(
+            settings
+                .editor_env
+                .iter()
+                .map(|(name, s)| (name.trim_start_matches("IGR_"), Some(string(s)))),
+        )
+        .filter_map(|(name, setting)| {
+            setting.map(|(value, source)| (format!("{} = {}", name.to_lowercase(), value), source))
+        })
+        .collect_vec();
+        let width = rows
+            .iter()
+            .map(|(row, _)| row.len())
+            .max()
+            .unwrap_or_default();
+
+        rows.iter()
+            .for_each(|(row, source)| println!("{:width$}  # {}", row, source));
+
+        [("queries", files.queries()), ("aliases", files.aliases())]
+            .into_iter()
+            .filter(|(_, entries)| !entries.is_empty())
+            .for_each(|(table, entries)| {
+                println!("\n[{}]", table);
+                entries
+                    .iter()
+                    .for_each(|(name, value)| println!("{} = {:?}", name, value));
+            });
+    }
+
+    /// Reports the files that were not searched to stderr, listing those that could not be decoded.
+    fn print_skipped(result: &SearchResult) {
+        if let Some(summary) = result.stat().skipped_summary() {
+            eprintln!("{}", format!("Skipped {}", summary).bold().yellow());
+            result
+                .skipped()
+                .iter()
+                .filter(|(_, skip)| *skip != Skip::Binary)
+                .for_each(|(path, skip)| eprintln!("{}: {}", path, skip));
+        }
+    }
+
+    fn check_skipped(skipped: Vec<String>) -> Result<()> {
+        if skipped.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "Skipped {} files modified since the search:\n{}",
+                skipped.len(),
+                skipped.join("\n")
+            ))
+        }
     }
 
     fn is_cli(&self) -> bool {
-        self.disable_tui || self.replace || self.count || self.count_matches || self.quiet
+        self.disable_tui
+            || self.diff
+            || self.json
+            || self.replace
+            || self.count
+            || self.count_matches
+            || self.quiet
     }
 }
@@ -1,16 +1,26 @@
+mod and;
 mod contains;
 mod ends_with;
+mod file_ext;
+mod file_path;
 mod invert_match;
 mod invert_match_regex;
 mod length;
+mod not;
+mod or;
 mod regex;
 mod starts_with;
 
+pub use self::and::And;
 pub use self::contains::Contains;
 pub use self::ends_with::EndsWith;
+pub use self::file_ext::FileExt;
+pub use self::file_path::FilePath;
 pub use self::invert_match::InvertMatch;
 pub use self::invert_match_regex::InvertMatchRegex;
 pub use self::length::Length;
+pub use self::not::Not;
+pub use self::or::Or;
 pub use self::regex::Regex;
 pub use self::starts_with::StartsWith;
 use std::fmt::{Debug, Display};
@@ -0,0 +1,59 @@
+use crate::models::search_condition::AND;
+
+use super::Filter;
+use std::{
+    fmt::{Debug, Display},
+    sync::Arc,
+};
+
+pub struct And {
+    lhs: Arc<dyn Filter>,
+    rhs: Arc<dyn Filter>,
+}
+
+impl And {
+    pub fn new(lhs: Arc<dyn Filter>, rhs: Arc<dyn Filter>) -> Arc<Self> {
+        Arc::new(And { lhs, rhs })
+    }
+}
+
+impl Filter for And {
+    fn filter(&self, text: &str) -> bool {
+        self.lhs.filter(text) && self.rhs.filter(text)
+    }
+}
+
+impl Debug for And {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl Display for And {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "({} {} {})", self.lhs, AND, self.rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::Contains;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("test", "string", "test_string", true)]
+    #[case("test", "none", "test_string", false)]
+    #[case("none", "string", "test_string", false)]
+    fn test(
+        #[case] lhs: String,
+        #[case] rhs: String,
+        #[case] text: String,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(
+            And::new(Contains::new(lhs), Contains::new(rhs)).filter(text.as_str()),
+            expected
+        )
+    }
+}
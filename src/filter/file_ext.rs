@@ -0,0 +1,57 @@
+use crate::models::search_condition::FILE_EXT;
+
+use super::Filter;
+use std::{
+    fmt::{Debug, Display},
+    path::Path,
+    sync::Arc,
+};
+
+/// Matches file paths by extension, given with or without the leading dot.
+pub struct FileExt {
+    ext: String,
+}
+
+impl FileExt {
+    pub fn new(ext: String) -> Arc<Self> {
+        Arc::new(FileExt {
+            ext: ext.trim_start_matches('.').to_string(),
+        })
+    }
+}
+
+impl Filter for FileExt {
+    fn filter(&self, text: &str) -> bool {
+        Path::new(text)
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case(&self.ext))
+    }
+}
+
+impl Debug for FileExt {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl Display for FileExt {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}({})", FILE_EXT, self.ext)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("rs", "src/main.rs", true)]
+    #[case(".rs", "./src/main.rs", true)]
+    #[case("md", "README.MD", true)]
+    #[case("rs", "src/main.rs.bak", false)]
+    #[case("rs", "rs", false)]
+    fn test(#[case] ext: String, #[case] path: String, #[case] expected: bool) {
+        assert_eq!(FileExt::new(ext).filter(path.as_str()), expected)
+    }
+}
@@ -0,0 +1,77 @@
+use crate::models::search_condition::FILE_PATH;
+
+use super::Filter;
+use anyhow::Result;
+use globset::{Glob, GlobBuilder, GlobMatcher};
+use std::{
+    fmt::{Debug, Display},
+    path::Path,
+    sync::Arc,
+};
+
+/// Matches file paths with a glob. A glob without `/` matches the file name,
+/// otherwise it matches the path relative to the current directory.
+pub struct FilePath {
+    glob: Glob,
+    matcher: GlobMatcher,
+}
+
+impl FilePath {
+    pub fn new(glob: String) -> Result<Arc<Self>> {
+        let glob = GlobBuilder::new(glob.trim_start_matches("./"))
+            .literal_separator(true)
+            .build()?;
+        let matcher = glob.compile_matcher();
+
+        Ok(Arc::new(FilePath { glob, matcher }))
+    }
+}
+
+impl Filter for FilePath {
+    fn filter(&self, text: &str) -> bool {
+        let path = Path::new(text);
+        let path = path.strip_prefix(".").unwrap_or(path);
+
+        if self.glob.glob().contains('/') {
+            self.matcher.is_match(path)
+        } else {
+            path.file_name()
+                .is_some_and(|name| self.matcher.is_match(name))
+        }
+    }
+}
+
+impl Debug for FilePath {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl Display for FilePath {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}({})", FILE_PATH, self.glob)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("src/**", "./src/models/search.rs", true)]
+    #[case("src/**", "tests/search.rs", false)]
+    #[case("src/*.rs", "src/models/search.rs", false)]
+    #[case("./src/*.rs", "src/main.rs", true)]
+    #[case("*.rs", "src/models/search.rs", true)]
+    #[case("search.*", "./src/models/search.rs", true)]
+    #[case("*.md", "src/models/search.rs", false)]
+    fn test(#[case] glob: String, #[case] path: String, #[case] expected: bool) {
+        assert_eq!(FilePath::new(glob).unwrap().filter(path.as_str()), expected)
+    }
+
+    #[test]
+    fn test_new_error() {
+        assert!(FilePath::new("src/{a".to_string()).is_err());
+    }
+}
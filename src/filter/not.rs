@@ -0,0 +1,52 @@
+use crate::models::search_condition::NOT;
+
+use super::Filter;
+use std::{
+    fmt::{Debug, Display},
+    sync::Arc,
+};
+
+pub struct Not {
+    filter: Arc<dyn Filter>,
+}
+
+impl Not {
+    pub fn new(filter: Arc<dyn Filter>) -> Arc<Self> {
+        Arc::new(Not { filter })
+    }
+}
+
+impl Filter for Not {
+    fn filter(&self, text: &str) -> bool {
+        !self.filter.filter(text)
+    }
+}
+
+impl Debug for Not {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl Display for Not {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}{}", NOT, self.filter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::Contains;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("test", "test_string", false)]
+    #[case("none", "test_string", true)]
+    fn test(#[case] keyword: String, #[case] text: String, #[case] expected: bool) {
+        assert_eq!(
+            Not::new(Contains::new(keyword)).filter(text.as_str()),
+            expected
+        )
+    }
+}
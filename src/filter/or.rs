@@ -0,0 +1,59 @@
+use crate::models::search_condition::OR;
+
+use super::Filter;
+use std::{
+    fmt::{Debug, Display},
+    sync::Arc,
+};
+
+pub struct Or {
+    lhs: Arc<dyn Filter>,
+    rhs: Arc<dyn Filter>,
+}
+
+impl Or {
+    pub fn new(lhs: Arc<dyn Filter>, rhs: Arc<dyn Filter>) -> Arc<Self> {
+        Arc::new(Or { lhs, rhs })
+    }
+}
+
+impl Filter for Or {
+    fn filter(&self, text: &str) -> bool {
+        self.lhs.filter(text) || self.rhs.filter(text)
+    }
+}
+
+impl Debug for Or {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl Display for Or {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "({} {} {})", self.lhs, OR, self.rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::Contains;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("test", "string", "test_string", true)]
+    #[case("test", "none", "test_string", true)]
+    #[case("none", "empty", "test_string", false)]
+    fn test(
+        #[case] lhs: String,
+        #[case] rhs: String,
+        #[case] text: String,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(
+            Or::new(Contains::new(lhs), Contains::new(rhs)).filter(text.as_str()),
+            expected
+        )
+    }
+}
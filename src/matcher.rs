@@ -1,20 +1,48 @@
+use crate::models::Captures;
 use std::fmt::{Debug, Display};
 use std::ops::Range;
 
+mod and;
 mod exact;
 mod ignore_case;
+mod line_filter;
+mod not;
 mod number;
+mod or;
+mod prefilter;
 mod regex;
 mod whole_word;
 
+pub use self::and::And;
 pub use self::exact::Exact;
 pub use self::ignore_case::IgnoreCase;
+pub use self::line_filter::LineFilter;
+pub use self::not::Not;
 pub use self::number::Number;
+pub use self::or::Or;
+pub use self::prefilter::Prefilter;
 pub use self::regex::Regex;
 pub use self::whole_word::WholeWord;
 
 pub trait Matcher: Debug + Display + Send + Sync + 'static {
     fn find(&self, text: &str) -> Vec<(String, Range<usize>)>;
+
+    fn is_match(&self, text: &str) -> bool {
+        !self.find(text).is_empty()
+    }
+
+    fn find_captures(&self, text: &str) -> Vec<(String, Range<usize>, Captures)> {
+        self.find(text)
+            .into_iter()
+            .map(|(t, r)| (t, r, Captures::default()))
+            .collect()
+    }
+
+    /// Literals of which every match contains at least one, used to skip text that cannot match.
+    /// `None` if a match does not need any literal.
+    fn literals(&self) -> Option<Vec<Vec<u8>>> {
+        None
+    }
 }
 
 impl PartialEq for dyn Matcher {
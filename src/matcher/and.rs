@@ -0,0 +1,104 @@
+use crate::models::search_condition::AND;
+
+use super::Matcher;
+use crate::models::Captures;
+use itertools::Itertools;
+use std::fmt::{Debug, Display};
+use std::ops::Range;
+use std::sync::Arc;
+
+pub struct And {
+    lhs: Arc<dyn Matcher>,
+    rhs: Arc<dyn Matcher>,
+}
+
+impl And {
+    pub fn new(lhs: Arc<dyn Matcher>, rhs: Arc<dyn Matcher>) -> Arc<Self> {
+        Arc::new(And { lhs, rhs })
+    }
+}
+
+impl Matcher for And {
+    fn find(&self, text: &str) -> Vec<(String, Range<usize>)> {
+        if self.is_match(text) {
+            itertools::concat(vec![self.lhs.find(text), self.rhs.find(text)])
+                .into_iter()
+                .unique()
+                .collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn is_match(&self, text: &str) -> bool {
+        self.lhs.is_match(text) && self.rhs.is_match(text)
+    }
+
+    fn find_captures(&self, text: &str) -> Vec<(String, Range<usize>, Captures)> {
+        if self.is_match(text) {
+            itertools::concat(vec![
+                self.lhs.find_captures(text),
+                self.rhs.find_captures(text),
+            ])
+            .into_iter()
+            .unique()
+            .collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn literals(&self) -> Option<Vec<Vec<u8>>> {
+        self.lhs.literals().or_else(|| self.rhs.literals())
+    }
+}
+
+impl Debug for And {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl Display for And {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "({} {} {})", self.lhs, AND, self.rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matcher::{Exact, Not};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(
+        Exact::new("test".to_string()),
+        Exact::new("string".to_string()),
+        "test_string",
+        vec![
+            ("test".to_string(), Range{start: 0, end: 4}),
+            ("string".to_string(), Range{start: 5, end: 11})
+        ]
+    )]
+    #[case(
+        Exact::new("test".to_string()),
+        Exact::new("none".to_string()),
+        "test_string",
+        Vec::new()
+    )]
+    #[case(
+        Exact::new("test".to_string()),
+        Not::new(Exact::new("none".to_string())),
+        "test_string",
+        vec![("test".to_string(), Range{start: 0, end: 4})]
+    )]
+    fn test(
+        #[case] lhs: Arc<dyn Matcher>,
+        #[case] rhs: Arc<dyn Matcher>,
+        #[case] text: String,
+        #[case] expected: Vec<(String, Range<usize>)>,
+    ) {
+        assert_eq!(And::new(lhs, rhs).find(text.as_str()), expected)
+    }
+}
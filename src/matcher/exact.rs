@@ -27,6 +27,10 @@ impl Matcher for Exact {
             })
             .collect()
     }
+
+    fn literals(&self) -> Option<Vec<Vec<u8>>> {
+        (!self.keyword.is_empty()).then(|| vec![self.keyword.as_bytes().to_vec()])
+    }
 }
 
 impl Debug for Exact {
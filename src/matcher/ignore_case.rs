@@ -1,5 +1,6 @@
 use crate::models::search_condition::IGNORE_CASE;
 
+use super::prefilter::regex_literals;
 use super::Matcher;
 use std::fmt::{Debug, Display};
 use std::ops::Range;
@@ -30,6 +31,15 @@ impl Matcher for IgnoreCase {
             })
             .collect()
     }
+
+    fn literals(&self) -> Option<Vec<Vec<u8>>> {
+        // `İ` is the only character whose lowercase has more than one character, which case folding does not cover.
+        if self.keyword.to_lowercase().contains('\u{307}') {
+            return None;
+        }
+
+        regex_literals(&format!("(?i){}", regex::escape(&self.keyword)))
+    }
 }
 
 impl Debug for IgnoreCase {
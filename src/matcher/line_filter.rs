@@ -0,0 +1,55 @@
+use super::Matcher;
+use crate::filter::Filter;
+use std::fmt::{Debug, Display};
+use std::ops::Range;
+use std::sync::Arc;
+
+/// Adapts a line filter so it can be combined with matchers in a boolean expression.
+/// It selects lines but never produces matches of its own.
+pub struct LineFilter {
+    filter: Arc<dyn Filter>,
+}
+
+impl LineFilter {
+    pub fn new(filter: Arc<dyn Filter>) -> Arc<Self> {
+        Arc::new(LineFilter { filter })
+    }
+}
+
+impl Matcher for LineFilter {
+    fn find(&self, _text: &str) -> Vec<(String, Range<usize>)> {
+        Vec::new()
+    }
+
+    fn is_match(&self, text: &str) -> bool {
+        self.filter.filter(text)
+    }
+}
+
+impl Debug for LineFilter {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl Display for LineFilter {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.filter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::Contains;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("test", "test_string", true)]
+    #[case("none", "test_string", false)]
+    fn test(#[case] keyword: String, #[case] text: String, #[case] expected: bool) {
+        let matcher = LineFilter::new(Contains::new(keyword));
+        assert_eq!(matcher.is_match(text.as_str()), expected);
+        assert!(matcher.find(text.as_str()).is_empty())
+    }
+}
@@ -0,0 +1,54 @@
+use crate::models::search_condition::NOT;
+
+use super::Matcher;
+use std::fmt::{Debug, Display};
+use std::ops::Range;
+use std::sync::Arc;
+
+pub struct Not {
+    matcher: Arc<dyn Matcher>,
+}
+
+impl Not {
+    pub fn new(matcher: Arc<dyn Matcher>) -> Arc<Self> {
+        Arc::new(Not { matcher })
+    }
+}
+
+impl Matcher for Not {
+    fn find(&self, _text: &str) -> Vec<(String, Range<usize>)> {
+        Vec::new()
+    }
+
+    fn is_match(&self, text: &str) -> bool {
+        !self.matcher.is_match(text)
+    }
+}
+
+impl Debug for Not {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl Display for Not {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}{}", NOT, self.matcher)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matcher::Exact;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("test", "test_string", false)]
+    #[case("none", "test_string", true)]
+    fn test(#[case] keyword: String, #[case] text: String, #[case] expected: bool) {
+        let not = Not::new(Exact::new(keyword));
+        assert_eq!(not.is_match(text.as_str()), expected);
+        assert!(not.find(text.as_str()).is_empty())
+    }
+}
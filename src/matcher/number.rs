@@ -62,6 +62,10 @@ impl Matcher for Number {
             })
             .collect()
     }
+
+    fn literals(&self) -> Option<Vec<Vec<u8>>> {
+        Some((b'0'..=b'9').map(|digit| vec![digit]).collect())
+    }
 }
 
 impl Debug for Number {
@@ -0,0 +1,103 @@
+use crate::models::search_condition::OR;
+
+use super::Matcher;
+use crate::models::Captures;
+use itertools::Itertools;
+use std::fmt::{Debug, Display};
+use std::ops::Range;
+use std::sync::Arc;
+
+pub struct Or {
+    lhs: Arc<dyn Matcher>,
+    rhs: Arc<dyn Matcher>,
+}
+
+impl Or {
+    pub fn new(lhs: Arc<dyn Matcher>, rhs: Arc<dyn Matcher>) -> Arc<Self> {
+        Arc::new(Or { lhs, rhs })
+    }
+}
+
+impl Matcher for Or {
+    fn find(&self, text: &str) -> Vec<(String, Range<usize>)> {
+        itertools::concat(vec![self.lhs.find(text), self.rhs.find(text)])
+            .into_iter()
+            .unique()
+            .collect()
+    }
+
+    fn is_match(&self, text: &str) -> bool {
+        self.lhs.is_match(text) || self.rhs.is_match(text)
+    }
+
+    fn find_captures(&self, text: &str) -> Vec<(String, Range<usize>, Captures)> {
+        itertools::concat(vec![
+            self.lhs.find_captures(text),
+            self.rhs.find_captures(text),
+        ])
+        .into_iter()
+        .unique()
+        .collect()
+    }
+
+    fn literals(&self) -> Option<Vec<Vec<u8>>> {
+        Some(itertools::concat(vec![
+            self.lhs.literals()?,
+            self.rhs.literals()?,
+        ]))
+    }
+}
+
+impl Debug for Or {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl Display for Or {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "({} {} {})", self.lhs, OR, self.rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matcher::Exact;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(
+        "test",
+        "string",
+        "test_string",
+        vec![
+            ("test".to_string(), Range{start: 0, end: 4}),
+            ("string".to_string(), Range{start: 5, end: 11})
+        ]
+    )]
+    #[case(
+        "none",
+        "string",
+        "test_string",
+        vec![("string".to_string(), Range{start: 5, end: 11})]
+    )]
+    #[case(
+        "test",
+        "test",
+        "test_string",
+        vec![("test".to_string(), Range{start: 0, end: 4})]
+    )]
+    #[case("none", "empty", "test_string", Vec::new())]
+    fn test(
+        #[case] lhs: String,
+        #[case] rhs: String,
+        #[case] text: String,
+        #[case] expected: Vec<(String, Range<usize>)>,
+    ) {
+        assert_eq!(
+            Or::new(Exact::new(lhs), Exact::new(rhs)).find(text.as_str()),
+            expected
+        )
+    }
+}
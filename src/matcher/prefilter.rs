@@ -0,0 +1,113 @@
+use super::Matcher;
+use aho_corasick::{AhoCorasick, MatchKind};
+use itertools::Itertools;
+use memchr::memmem;
+use regex_syntax::hir::literal::{ExtractKind, Extractor};
+
+/// Finds the places in a buffer where a matcher can match, from the literals that every match contains,
+/// so that the rest of the buffer can be skipped without running the matcher on each line.
+pub struct Prefilter {
+    searcher: Searcher,
+}
+
+enum Searcher {
+    Memmem(Box<memmem::Finder<'static>>),
+    AhoCorasick(AhoCorasick),
+}
+
+impl Prefilter {
+    pub fn new(matcher: &dyn Matcher) -> Option<Self> {
+        // Tabs are replaced by spaces before matching, so only the parts between spaces can be looked for.
+        let literals = matcher
+            .literals()?
+            .iter()
+            .map(|literal| {
+                literal
+                    .split(|b| *b == b' ')
+                    .max_by_key(|part| part.len())
+                    .filter(|part| !part.is_empty())
+                    .map(|part| part.to_vec())
+            })
+            .collect::<Option<Vec<_>>>()?
+            .into_iter()
+            .unique()
+            .collect_vec();
+
+        let searcher = match literals.as_slice() {
+            [] => return None,
+            [literal] => Searcher::Memmem(Box::new(memmem::Finder::new(literal).into_owned())),
+            _ => Searcher::AhoCorasick(
+                AhoCorasick::builder()
+                    .match_kind(MatchKind::LeftmostFirst)
+                    .build(&literals)
+                    .ok()?,
+            ),
+        };
+
+        Some(Self { searcher })
+    }
+
+    /// Returns the start of the first place at or after `start` where a match may be.
+    pub fn find(&self, haystack: &str, start: usize) -> Option<usize> {
+        let haystack = &haystack.as_bytes()[start..];
+
+        match &self.searcher {
+            Searcher::Memmem(finder) => finder.find(haystack),
+            Searcher::AhoCorasick(ac) => ac.find(haystack).map(|m| m.start()),
+        }
+        .map(|i| start + i)
+    }
+}
+
+/// The literals that every match of the regex starts with, or `None` if a match can start anywhere.
+pub fn regex_literals(pattern: &str) -> Option<Vec<Vec<u8>>> {
+    let hir = regex_syntax::parse(pattern).ok()?;
+    let mut seq = Extractor::new().kind(ExtractKind::Prefix).extract(&hir);
+
+    seq.optimize_for_prefix_by_preference();
+    seq.literals()
+        .filter(|literals| !literals.is_empty() && literals.iter().all(|l| !l.is_empty()))
+        .map(|literals| literals.iter().map(|l| l.as_bytes().to_vec()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matcher::{Exact, IgnoreCase, Not, Number, Or, Regex, WholeWord};
+    use crate::models::value::{Op, Value};
+    use rstest::rstest;
+    use std::sync::Arc;
+
+    #[rstest]
+    #[case(Exact::new("foo".to_string()), "bar\nbaz foo", Some(8))]
+    #[case(Exact::new("foo".to_string()), "bar\nbaz", None)]
+    #[case(Exact::new("foo bar".to_string()), "foo\tbar", Some(4))]
+    #[case(IgnoreCase::new("foo".to_string()), "bar FOO", Some(4))]
+    #[case(IgnoreCase::new("kit".to_string()), "\u{212a}IT", Some(0))]
+    #[case(WholeWord::new("foo".to_string()), "foobar", Some(0))]
+    #[case(Regex::new("ba[rz]".to_string()).unwrap(), "foo baz", Some(4))]
+    #[case(Regex::new("(?i)error|warn".to_string()).unwrap(), "a\nWarning", Some(2))]
+    #[case(Number::new(Op::Gt(Value::Num(1))), "a\nb1", Some(3))]
+    #[case(Or::new(Exact::new("foo".to_string()), Exact::new("bar".to_string())), "baz bar foo", Some(4))]
+    fn test_find(
+        #[case] matcher: Arc<dyn Matcher>,
+        #[case] haystack: &str,
+        #[case] expected: Option<usize>,
+    ) {
+        assert_eq!(
+            Prefilter::new(matcher.as_ref()).and_then(|p| p.find(haystack, 0)),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(Exact::new("".to_string()))]
+    #[case(Exact::new("  ".to_string()))]
+    #[case(Regex::new(".*".to_string()).unwrap())]
+    #[case(Regex::new("\\w+foo".to_string()).unwrap())]
+    #[case(Not::new(Exact::new("foo".to_string())))]
+    #[case(Or::new(Exact::new("foo".to_string()), Not::new(Exact::new("bar".to_string()))))]
+    fn test_no_prefilter(#[case] matcher: Arc<dyn Matcher>) {
+        assert!(Prefilter::new(matcher.as_ref()).is_none());
+    }
+}
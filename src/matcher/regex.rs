@@ -1,4 +1,6 @@
+use super::prefilter::regex_literals;
 use super::Matcher;
+use crate::models::Captures;
 use anyhow::Result;
 use std::fmt::{Debug, Display};
 use std::ops::Range;
@@ -22,6 +24,25 @@ impl Matcher for Regex {
             .map(|m| (text[m.start()..m.end()].to_string(), m.range()))
             .collect()
     }
+
+    fn find_captures(&self, text: &str) -> Vec<(String, Range<usize>, Captures)> {
+        self.re
+            .captures_iter(text)
+            .filter_map(|c| {
+                c.get(0).map(|m| {
+                    (
+                        m.as_str().to_string(),
+                        m.range(),
+                        Captures::new(&self.re, &c),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    fn literals(&self) -> Option<Vec<Vec<u8>>> {
+        regex_literals(self.re.as_str())
+    }
 }
 
 impl Debug for Regex {
@@ -60,4 +81,17 @@ mod tests {
     ) {
         assert_eq!(Regex::new(regex).unwrap().find(text.as_str()), expected)
     }
+
+    #[test]
+    fn test_find_captures() {
+        let captures = Regex::new(r"foo\((?<arg>\w+)\)".to_string())
+            .unwrap()
+            .find_captures("foo(bar)");
+        let (text, range, captures) = captures.first().unwrap();
+
+        assert_eq!(text, "foo(bar)");
+        assert_eq!(range, &Range { start: 0, end: 8 });
+        assert_eq!(captures.get(1), Some("bar"));
+        assert_eq!(captures.name("arg"), Some("bar"));
+    }
 }
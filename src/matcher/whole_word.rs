@@ -26,6 +26,10 @@ impl Matcher for WholeWord {
             .map(|m| (self.keyword.clone(), m.range()))
             .collect()
     }
+
+    fn literals(&self) -> Option<Vec<Vec<u8>>> {
+        (!self.keyword.is_empty()).then(|| vec![self.keyword.as_bytes().to_vec()])
+    }
 }
 
 impl Debug for WholeWord {
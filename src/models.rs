@@ -1,24 +1,45 @@
+pub mod cancellation_token;
+pub mod captures;
 pub mod config;
+pub mod config_file;
+pub mod decoder;
 pub mod editor;
 pub mod file_preview;
 pub mod file_result;
+pub mod file_stamp;
+pub mod grep_input;
+pub mod history;
 pub mod line;
+pub mod line_reader;
 pub mod match_result;
 pub mod search;
 pub mod search_condition;
 pub mod search_result;
+pub mod text_file;
+pub mod undo_journal;
 pub mod value;
+pub mod walk_filter;
 
+pub use self::cancellation_token::CancellationToken;
+pub use self::captures::Captures;
 pub use self::config::SearchConfig;
 pub use self::config::SearchResultConfig;
+pub use self::config_file::ConfigFile;
+pub use self::decoder::Decoder;
 pub use self::editor::Editor;
 pub use self::editor::EditorCommand;
 pub use self::file_preview::FilePreview;
 pub use self::file_result::FileResult;
-pub use self::line::Line;
+pub use self::file_stamp::FileStamp;
+pub use self::grep_input::InputFormat;
+pub use self::history::History;
+pub use self::line::{Line, Position};
 pub use self::match_result::MatchResult;
 pub use self::search::Search;
 pub use self::search_condition::SearchCondition;
 pub use self::search_result::SearchResult;
 pub use self::search_result::Stat;
+pub use self::text_file::TextFile;
+pub use self::undo_journal::UndoJournal;
 pub use self::value::Value;
+pub use self::walk_filter::WalkFilter;
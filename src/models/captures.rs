@@ -0,0 +1,115 @@
+use itertools::Itertools;
+
+/// Capture groups of a regex match, kept so that later transforms can refer to them.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Captures {
+    groups: Vec<Option<String>>,
+    names: Vec<(String, usize)>,
+}
+
+impl Captures {
+    pub fn new(re: &regex::Regex, captures: &regex::Captures) -> Self {
+        Self {
+            groups: captures
+                .iter()
+                .map(|m| m.map(|m| m.as_str().to_string()))
+                .collect_vec(),
+            names: re
+                .capture_names()
+                .enumerate()
+                .filter_map(|(i, name)| name.map(|name| (name.to_string(), i)))
+                .collect_vec(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.groups.get(index).and_then(|g| g.as_deref())
+    }
+
+    pub fn name(&self, name: &str) -> Option<&str> {
+        self.names
+            .iter()
+            .find(|(n, _)| n == name)
+            .and_then(|(_, i)| self.get(*i))
+    }
+
+    /// Expands `$1`, `$name`, `${name}` and `$$` in the template, following the syntax of `regex::Captures::expand`.
+    /// Templates are returned unchanged when there are no captures.
+    pub fn expand(&self, template: &str) -> String {
+        if self.is_empty() {
+            return template.to_string();
+        }
+
+        let mut expanded = String::new();
+        let mut rest = template;
+
+        while let Some(i) = rest.find('$') {
+            expanded.push_str(&rest[..i]);
+            rest = &rest[i + 1..];
+
+            if let Some(r) = rest.strip_prefix('$') {
+                expanded.push('$');
+                rest = r;
+                continue;
+            }
+
+            let (name, r) = match rest.strip_prefix('{').and_then(|r| r.split_once('}')) {
+                Some((name, r)) if !name.is_empty() => (name, r),
+                _ => {
+                    let end = rest
+                        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                        .unwrap_or(rest.len());
+                    (&rest[..end], &rest[end..])
+                }
+            };
+
+            if name.is_empty() {
+                expanded.push('$');
+            } else {
+                expanded.push_str(
+                    match name.parse::<usize>() {
+                        Ok(i) => self.get(i),
+                        Err(_) => self.name(name),
+                    }
+                    .unwrap_or_default(),
+                );
+                rest = r;
+            }
+        }
+
+        expanded.push_str(rest);
+        expanded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(r"foo\((\w+)\)", "foo(bar)", "baz($1, None)", "baz(bar, None)")]
+    #[case(r"(?<name>\w+)=(\d+)", "key=10", "${name}: $2", "key: 10")]
+    #[case(r"(\w+)", "test", "$1_x ${1}_x", " test_x")]
+    #[case(r"(\w+)", "test", "$$1 $", "$1 $")]
+    #[case(r"(\w+)", "test", "$9", "")]
+    fn test_expand(
+        #[case] regex: String,
+        #[case] text: String,
+        #[case] template: String,
+        #[case] expected: String,
+    ) {
+        let re = regex::Regex::new(&regex).unwrap();
+        let captures = Captures::new(&re, &re.captures(&text).unwrap());
+        assert_eq!(captures.expand(&template), expected)
+    }
+
+    #[test]
+    fn test_expand_without_captures() {
+        assert_eq!(Captures::default().expand("$1"), "$1")
+    }
+}
@@ -1,16 +1,34 @@
+use super::{Decoder, InputFormat, SearchCondition};
+use anyhow::{anyhow, Result};
+use std::{env, path::PathBuf};
+
 #[derive(Clone, Debug, Default)]
 pub struct SearchConfig {
     pub after_context: Option<usize>,
     pub before_context: Option<usize>,
     pub context_separator: String,
+    /// How files are decoded, set by `--encoding` and `--text`.
+    pub decoder: Decoder,
     pub exclude_path: Option<String>,
+    /// The `file.ext` and `file.path` conditions of the query, checked before a file is read.
+    pub file_filters: Vec<SearchCondition>,
+    pub globs: Vec<String>,
+    pub iglobs: Vec<String>,
     pub hide_help: bool,
     pub no_git_exclude: bool,
     pub no_git_ignore: bool,
     pub no_file_name: bool,
     pub no_line_no: bool,
     pub hidden: bool,
+    pub input_format: InputFormat,
     pub max_depth: Option<usize>,
+    /// Files larger than this many bytes are skipped.
+    pub max_filesize: Option<u64>,
+    /// Whether to read files through memory maps.
+    pub mmap: bool,
+    pub preview_theme: Option<String>,
+    pub types: Vec<String>,
+    pub types_not: Vec<String>,
     pub vimgrep: bool,
 }
 
@@ -36,3 +54,70 @@ impl SearchConfig {
         }
     }
 }
+
+/// `$XDG_CONFIG_HOME/igr`, or `~/.config/igr` if it is not set.
+pub fn config_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("igr"))
+}
+
+/// `$XDG_DATA_HOME/igr`, or `~/.local/share/igr` if it is not set.
+pub fn data_dir() -> Option<PathBuf> {
+    env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .map(|dir| dir.join("igr"))
+}
+
+/// `$XDG_STATE_HOME/igr`, or `~/.local/state/igr` if it is not set.
+pub fn state_dir() -> Option<PathBuf> {
+    env::var_os("XDG_STATE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state")))
+        .map(|dir| dir.join("igr"))
+}
+
+/// Parses a size in bytes with an optional `K`, `M` or `G` suffix, e.g. `50M`.
+pub fn parse_filesize(s: &str) -> Result<u64> {
+    let s = s.trim();
+    let (number, shift) = match s.char_indices().last() {
+        Some((i, 'K' | 'k')) => (&s[..i], 10),
+        Some((i, 'M' | 'm')) => (&s[..i], 20),
+        Some((i, 'G' | 'g')) => (&s[..i], 30),
+        _ => (s, 0),
+    };
+
+    number
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(1 << shift))
+        .ok_or_else(|| {
+            anyhow!(
+                "invalid size `{}`, expected a number with an optional K, M or G suffix",
+                s
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("100", Some(100))]
+    #[case("2K", Some(2048))]
+    #[case("50M", Some(50 * 1024 * 1024))]
+    #[case("1g", Some(1024 * 1024 * 1024))]
+    #[case("M", None)]
+    #[case("-1", None)]
+    #[case("1T", None)]
+    fn test_parse_filesize(#[case] s: &str, #[case] expected: Option<u64>) {
+        assert_eq!(parse_filesize(s).ok(), expected);
+    }
+}
@@ -0,0 +1,273 @@
+use super::config::config_dir;
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CONFIG_FILE: &str = "config.toml";
+const PROJECT_CONFIG_FILE: &str = ".igr.toml";
+
+/// Settings read from `config.toml` in the config directory or `.igr.toml` in a project.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFile {
+    pub after_context: Option<usize>,
+    pub before_context: Option<usize>,
+    pub context: Option<usize>,
+    pub context_separator: Option<String>,
+    pub copy_command: Option<String>,
+    pub custom_command: Option<String>,
+    pub editor: Option<String>,
+    pub encoding: Option<String>,
+    pub exclude_path: Option<String>,
+    pub github_branch_name: Option<String>,
+    pub github_repository_name: Option<String>,
+    pub github_user_name: Option<String>,
+    pub hidden: Option<bool>,
+    pub hide_help: Option<bool>,
+    pub max_depth: Option<usize>,
+    pub max_filesize: Option<String>,
+    pub no_git_ignore: Option<bool>,
+    pub no_icon: Option<bool>,
+    pub preview_theme: Option<String>,
+    pub theme: Option<String>,
+    pub url_open_command: Option<String>,
+    /// Saved queries, invoked as `@name`.
+    pub queries: BTreeMap<String, String>,
+    /// Condition aliases, invoked as `name(args)`.
+    pub aliases: BTreeMap<String, String>,
+}
+
+impl ConfigFile {
+    pub fn read(path: &Path) -> Result<Self> {
+        toml::from_str(&fs::read_to_string(path)?)
+            .map_err(|e| anyhow!("Invalid config file {}: {}", path.display(), e))
+    }
+
+    /// Reads a project config file, which may not set commands to run.
+    pub fn read_project(path: &Path) -> Result<Self> {
+        let config = Self::read(path)?;
+
+        match config.commands().first() {
+            Some(name) => Err(anyhow!(
+                "Invalid config file {}: `{}` is only allowed in the global config file",
+                path.display(),
+                name
+            )),
+            None => Ok(config),
+        }
+    }
+
+    /// The names of the settings that are set and run commands.
+    fn commands(&self) -> Vec<&'static str> {
+        [
+            ("copy_command", &self.copy_command),
+            ("custom_command", &self.custom_command),
+            ("url_open_command", &self.url_open_command),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.as_ref().map(|_| name))
+        .collect()
+    }
+}
+
+/// The global config file followed by the nearest project config file, if any.
+/// Settings in later files take precedence.
+#[derive(Clone, Debug, Default)]
+pub struct ConfigFiles {
+    files: Vec<(PathBuf, ConfigFile)>,
+}
+
+impl ConfigFiles {
+    pub fn load(dir: &Path) -> Result<Self> {
+        let global = config_dir()
+            .map(|dir| dir.join(CONFIG_FILE))
+            .filter(|path| path.is_file())
+            .map(|path| ConfigFile::read(&path).map(|config| (path, config)));
+        let project = dir
+            .ancestors()
+            .map(|dir| dir.join(PROJECT_CONFIG_FILE))
+            .find(|path| path.is_file())
+            .map(|path| ConfigFile::read_project(&path).map(|config| (path, config)));
+
+        Ok(Self {
+            files: global.into_iter().chain(project).collect::<Result<_>>()?,
+        })
+    }
+
+    /// Returns the value from the file with the highest precedence that sets it.
+    pub fn get<T>(&self, f: impl Fn(&ConfigFile) -> Option<T>) -> Option<Setting<T>> {
+        self.files.iter().rev().find_map(|(path, config)| {
+            f(config).map(|value| Setting::new(value, Source::File(path.clone())))
+        })
+    }
+
+    pub fn queries(&self) -> BTreeMap<String, String> {
+        self.files
+            .iter()
+            .flat_map(|(_, config)| config.queries.clone())
+            .collect()
+    }
+
+    pub fn aliases(&self) -> BTreeMap<String, String> {
+        self.files
+            .iter()
+            .flat_map(|(_, config)| config.aliases.clone())
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    Default,
+    CommandLine,
+    Env(&'static str),
+    File(PathBuf),
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::CommandLine => write!(f, "command line"),
+            Source::Env(name) => write!(f, "env {}", name),
+            Source::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// An effective setting and where it came from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Setting<T> {
+    pub value: T,
+    pub source: Source,
+}
+
+impl<T> Setting<T> {
+    pub fn new(value: T, source: Source) -> Self {
+        Self { value, source }
+    }
+
+    /// A value given on the command line, or by `env` when it equals the variable.
+    pub fn from_cli(value: Option<T>, env: Option<&'static str>) -> Option<Self>
+    where
+        T: ToString,
+    {
+        value.map(|value| {
+            let source = match env {
+                Some(name) if std::env::var(name).is_ok_and(|v| v == value.to_string()) => {
+                    Source::Env(name)
+                }
+                _ => Source::CommandLine,
+            };
+
+            Self::new(value, source)
+        })
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Setting<U> {
+        Setting::new(f(self.value), self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn test_from_str() {
+        let config: ConfigFile = toml::from_str(
+            r#"
+context = 2
+hidden = true
+editor = "neovim"
+
+[queries]
+deprecated = "line.contains(@deprecated) | snake_case()"
+
+[aliases]
+todo = "line.regex(TODO|FIXME) | whole_word({1})"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config,
+            ConfigFile {
+                context: Some(2),
+                hidden: Some(true),
+                editor: Some("neovim".to_string()),
+                queries: BTreeMap::from([(
+                    "deprecated".to_string(),
+                    "line.contains(@deprecated) | snake_case()".to_string()
+                )]),
+                aliases: BTreeMap::from([(
+                    "todo".to_string(),
+                    "line.regex(TODO|FIXME) | whole_word({1})".to_string()
+                )]),
+                ..ConfigFile::default()
+            }
+        );
+        assert!(toml::from_str::<ConfigFile>("hiden = true").is_err());
+    }
+
+    #[rstest]
+    #[case("context = 2", None)]
+    #[case("custom_command = \"sh -c 'touch pwned'\"", Some("custom_command"))]
+    #[case("editor = \"vim\"\ncopy_command = \"xclip\"", Some("copy_command"))]
+    #[case("url_open_command = \"open\"", Some("url_open_command"))]
+    fn test_read_project(#[case] toml: &str, #[case] expected: Option<&str>) {
+        let dir = std::env::temp_dir().join(format!(
+            "igr-project-{}-{}",
+            std::process::id(),
+            expected.unwrap_or("none")
+        ));
+        let path = dir.join(PROJECT_CONFIG_FILE);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, toml).unwrap();
+
+        assert_eq!(
+            ConfigFile::read_project(&path).err().map(|e| e.to_string()),
+            expected.map(|name| format!(
+                "Invalid config file {}: `{}` is only allowed in the global config file",
+                path.display(),
+                name
+            ))
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[rstest]
+    #[case(|c: &ConfigFile| c.context, Some(Setting::new(3, Source::File("project".into()))))]
+    #[case(|c: &ConfigFile| c.hidden.map(|h| h as usize), Some(Setting::new(1, Source::File("global".into()))))]
+    #[case(|c: &ConfigFile| c.max_depth, None)]
+    fn test_get(
+        #[case] f: fn(&ConfigFile) -> Option<usize>,
+        #[case] expected: Option<Setting<usize>>,
+    ) {
+        let files = ConfigFiles {
+            files: vec![
+                (
+                    "global".into(),
+                    ConfigFile {
+                        context: Some(2),
+                        hidden: Some(true),
+                        ..ConfigFile::default()
+                    },
+                ),
+                (
+                    "project".into(),
+                    ConfigFile {
+                        context: Some(3),
+                        ..ConfigFile::default()
+                    },
+                ),
+            ],
+        };
+
+        assert_eq!(files.get(f), expected);
+    }
+}
@@ -0,0 +1,292 @@
+use super::line_reader::LineReader;
+use super::text_file::BOM;
+use anyhow::{anyhow, Result};
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use memmap2::Mmap;
+use std::fmt::{self, Display, Formatter};
+use std::fs::File;
+use std::io::{self, Chain, Cursor, Read};
+
+/// The number of bytes checked for a NUL byte to detect binary files.
+const BINARY_CHECK_LEN: usize = 8192;
+
+/// The decoded lines of a file, starting with the bytes read to detect its encoding.
+pub type DecodedLines<R = Box<dyn Read>> = LineReader<Chain<Cursor<Vec<u8>>, R>>;
+
+/// The lines of a file, either decoded as they are read or, for a memory-mapped UTF-8 file,
+/// the mapped bytes themselves.
+pub enum FileLines {
+    Decoded(DecodedLines),
+    Mapped { map: Mmap, done: bool },
+}
+
+/// Decodes files by their BOM, or else in the `--encoding` encoding, or else as UTF-8.
+/// Files with a NUL byte near the start are binary and are not decoded unless `text` is set.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Decoder {
+    encoding: Option<&'static Encoding>,
+    text: bool,
+}
+
+/// Why a file could not be decoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Skip {
+    Binary,
+    Undecodable(&'static Encoding),
+    TooLarge,
+}
+
+#[derive(Debug)]
+pub enum ReadError {
+    Io(io::Error),
+    Skip(Skip),
+}
+
+impl Display for Skip {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Skip::Binary => write!(f, "binary file"),
+            Skip::Undecodable(encoding) => write!(f, "not valid {}", encoding.name()),
+            Skip::TooLarge => write!(f, "larger than --max-filesize"),
+        }
+    }
+}
+
+impl Display for ReadError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ReadError::Io(e) => write!(f, "{}", e),
+            ReadError::Skip(skip) => write!(f, "{}", skip),
+        }
+    }
+}
+
+impl std::error::Error for ReadError {}
+
+impl From<io::Error> for ReadError {
+    fn from(e: io::Error) -> Self {
+        ReadError::Io(e)
+    }
+}
+
+impl Decoder {
+    /// `encoding` is a WHATWG label such as `latin1` or `shift_jis`.
+    pub fn new(encoding: Option<&str>, text: bool) -> Result<Self> {
+        Ok(Self {
+            encoding: encoding
+                .map(|label| {
+                    Encoding::for_label(label.trim().as_bytes())
+                        .ok_or_else(|| anyhow!("Unknown encoding: {}", label))
+                })
+                .transpose()?,
+            text,
+        })
+    }
+
+    /// Reads and decodes the whole file. The text starts with `BOM` if the file has one.
+    pub fn read(&self, path: &str) -> Result<(String, &'static Encoding), ReadError> {
+        let lines = self.lines_of(File::open(path)?)?;
+        let encoding = lines.encoding();
+
+        Ok((lines.collect::<Result<String, _>>()?, encoding))
+    }
+
+    /// Opens the file to be read line by line, checking the start of the file for binary content
+    /// and a BOM before reading the rest. With `mmap`, the file is read through a memory map,
+    /// which is scanned in place if the file is UTF-8.
+    pub fn lines(&self, path: &str, mmap: bool) -> Result<FileLines, ReadError> {
+        let file = File::open(path)?;
+
+        if !mmap || file.metadata()?.len() == 0 {
+            return Ok(FileLines::Decoded(
+                self.lines_of(Box::new(file) as Box<dyn Read>)?,
+            ));
+        }
+
+        // SAFETY: the map is only read, and a file truncated while it is searched fails the search
+        // of that file at worst, as with ripgrep's `--mmap`.
+        let map = unsafe { Mmap::map(&file)? };
+        let head = &map[..map.len().min(BINARY_CHECK_LEN)];
+
+        if self.is_binary(head) {
+            Err(ReadError::Skip(Skip::Binary))
+        } else if Encoding::for_bom(head).map_or(self.encoding.unwrap_or(UTF_8), |(e, _)| e)
+            == UTF_8
+        {
+            Ok(FileLines::Mapped { map, done: false })
+        } else {
+            Ok(FileLines::Decoded(
+                self.lines_of(Box::new(Cursor::new(map)) as Box<dyn Read>)?,
+            ))
+        }
+    }
+
+    fn lines_of<R: Read>(&self, mut reader: R) -> Result<DecodedLines<R>, ReadError> {
+        let mut head = Vec::new();
+
+        reader
+            .by_ref()
+            .take(BINARY_CHECK_LEN as u64)
+            .read_to_end(&mut head)?;
+
+        if self.is_binary(&head) {
+            return Err(ReadError::Skip(Skip::Binary));
+        }
+
+        let (encoding, prefix) = match Encoding::for_bom(&head) {
+            Some((encoding, len)) => {
+                head.drain(..len);
+                (encoding, BOM.to_string())
+            }
+            None => (self.encoding.unwrap_or(UTF_8), String::new()),
+        };
+
+        Ok(LineReader::new(
+            Cursor::new(head).chain(reader),
+            encoding,
+            &prefix,
+        ))
+    }
+
+    fn is_binary(&self, bytes: &[u8]) -> bool {
+        !self.text
+            && !matches!(self.encoding, Some(e) if e == UTF_16LE || e == UTF_16BE)
+            && Encoding::for_bom(bytes).is_none()
+            && bytes.contains(&0)
+    }
+}
+
+impl FileLines {
+    pub fn encoding(&self) -> &'static Encoding {
+        match self {
+            FileLines::Decoded(lines) => lines.encoding(),
+            FileLines::Mapped { .. } => UTF_8,
+        }
+    }
+
+    /// Returns the whole lines read so far, or the whole mapped file at once.
+    pub fn next_block(&mut self) -> Option<Result<&str, ReadError>> {
+        match self {
+            FileLines::Decoded(lines) => lines.next_block(),
+            FileLines::Mapped { map, done } => (!std::mem::replace(done, true)).then(|| {
+                std::str::from_utf8(map).map_err(|_| ReadError::Skip(Skip::Undecodable(UTF_8)))
+            }),
+        }
+    }
+}
+
+/// The number of bytes the text takes in the encoding, without the cost of encoding UTF-8 or ASCII text.
+pub fn encoded_len(text: &str, encoding: &'static Encoding) -> usize {
+    if encoding == UTF_16LE || encoding == UTF_16BE {
+        text.encode_utf16().count() * 2
+    } else if encoding == UTF_8 || text.is_ascii() {
+        text.len()
+    } else {
+        encoding.encode(text).0.len()
+    }
+}
+
+/// Encodes the text in the encoding it was read in. Fails if a character cannot be represented in it.
+pub fn encode(text: &str, encoding: &'static Encoding) -> Result<Vec<u8>> {
+    if encoding == UTF_16LE {
+        Ok(text.encode_utf16().flat_map(u16::to_le_bytes).collect())
+    } else if encoding == UTF_16BE {
+        Ok(text.encode_utf16().flat_map(u16::to_be_bytes).collect())
+    } else {
+        match encoding.encode(text) {
+            (_, _, true) => Err(anyhow!("The text cannot be encoded in {}", encoding.name())),
+            (bytes, _, false) => Ok(bytes.into_owned()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encoding_rs::{SHIFT_JIS, WINDOWS_1252};
+    use rstest::rstest;
+
+    fn decode(decoder: Decoder, bytes: &[u8]) -> Result<(String, &'static Encoding), ReadError> {
+        let lines = decoder.lines_of(bytes)?;
+        let encoding = lines.encoding();
+
+        Ok((lines.collect::<Result<String, _>>()?, encoding))
+    }
+
+    #[rstest]
+    #[case(None, false, b"foo\n".to_vec(), Some(("foo\n".to_string(), UTF_8)))]
+    #[case(None, false, b"\xef\xbb\xbffoo".to_vec(), Some(("\u{feff}foo".to_string(), UTF_8)))]
+    #[case(None, false, b"\xff\xfef\x00o\x00".to_vec(), Some(("\u{feff}fo".to_string(), UTF_16LE)))]
+    #[case(Some("latin1"), false, b"caf\xe9".to_vec(), Some(("café".to_string(), WINDOWS_1252)))]
+    #[case(Some("shift_jis"), false, b"\x82\xa0".to_vec(), Some(("あ".to_string(), SHIFT_JIS)))]
+    #[case(None, false, b"caf\xe9".to_vec(), None)]
+    #[case(None, false, b"foo\x00bar".to_vec(), None)]
+    #[case(None, true, b"foo\x00bar".to_vec(), Some(("foo\0bar".to_string(), UTF_8)))]
+    fn test_decode(
+        #[case] encoding: Option<&str>,
+        #[case] text: bool,
+        #[case] bytes: Vec<u8>,
+        #[case] expected: Option<(String, &'static Encoding)>,
+    ) {
+        assert_eq!(
+            decode(Decoder::new(encoding, text).unwrap(), &bytes).ok(),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(None, b"\xef\xbb\xbffoo\nbar".to_vec(), true, Some("\u{feff}foo\nbar".to_string()))]
+    #[case(Some("latin1"), b"caf\xe9\n".to_vec(), false, Some("café\n".to_string()))]
+    #[case(None, b"caf\xe9\n".to_vec(), true, None)]
+    fn test_lines_mmap(
+        #[case] encoding: Option<&str>,
+        #[case] bytes: Vec<u8>,
+        #[case] mapped: bool,
+        #[case] expected: Option<String>,
+    ) {
+        let path = std::env::temp_dir().join(format!(
+            "igr-mmap-{}-{}-{}",
+            std::process::id(),
+            encoding.unwrap_or("none"),
+            bytes.len()
+        ));
+        std::fs::write(&path, &bytes).unwrap();
+
+        let mut lines = Decoder::new(encoding, false)
+            .unwrap()
+            .lines(path.to_str().unwrap(), true)
+            .unwrap();
+
+        assert_eq!(matches!(lines, FileLines::Mapped { .. }), mapped);
+        assert_eq!(
+            lines
+                .next_block()
+                .unwrap()
+                .ok()
+                .map(|block| block.to_string()),
+            expected
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[rstest]
+    #[case(b"foo\x00bar".to_vec(), Skip::Binary)]
+    #[case(b"caf\xe9".to_vec(), Skip::Undecodable(UTF_8))]
+    fn test_decode_skip(#[case] bytes: Vec<u8>, #[case] expected: Skip) {
+        assert!(
+            matches!(decode(Decoder::default(), &bytes), Err(ReadError::Skip(skip)) if skip == expected)
+        );
+    }
+
+    #[rstest]
+    #[case("café", WINDOWS_1252, Some(b"caf\xe9".to_vec()))]
+    #[case("\u{feff}fo", UTF_16LE, Some(b"\xff\xfef\x00o\x00".to_vec()))]
+    #[case("あ", WINDOWS_1252, None)]
+    fn test_encode(
+        #[case] text: &str,
+        #[case] encoding: &'static Encoding,
+        #[case] expected: Option<Vec<u8>>,
+    ) {
+        assert_eq!(encode(text, encoding).ok(), expected);
+    }
+}
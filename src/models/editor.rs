@@ -76,10 +76,11 @@ impl EditorCommand {
 
     fn args(&self, file_path: &str, line_no: usize) -> Result<Vec<String>> {
         match &self.editor {
-            Editor::Custom(command) => {
-                let command = command.replace("$line_no", &line_no.to_string());
-                Ok(command.replace("$file_path", &line_no.to_string()))
-            }
+            Editor::Custom(command) => Ok(command
+                .replace("$line_no", &line_no.to_string())
+                .replace("{line_no}", &line_no.to_string())
+                .replace("$file_path", file_path)
+                .replace("{file_path}", file_path)),
             Editor::Emacs => Ok(format!("{} -nw +{line_no} {file_path}", self.command())),
             Editor::Intellij => Ok(format!("{} --line {line_no} {file_path}", self.command())),
             Editor::Less | Editor::Nano | Editor::Neovim | Editor::Vim => {
@@ -1,8 +1,11 @@
-use std::{fs, ops::Range};
+use std::ops::Range;
 
+use super::Decoder;
 use anyhow::{anyhow, Result};
 use itertools::Itertools;
 
+const TAB: &str = "    ";
+
 #[derive(Clone, Debug)]
 pub struct FilePreview {
     file_path: String,
@@ -10,25 +13,54 @@ pub struct FilePreview {
 }
 
 impl FilePreview {
-    pub fn new(file_path: String) -> Result<Self> {
-        match fs::read_to_string(file_path.clone()) {
-            Ok(s) => Ok(Self {
-                file_path,
-                text: s.replace('\t', "    "),
-            }),
+    pub fn new(file_path: String, decoder: &Decoder) -> Result<Self> {
+        match decoder.read(&file_path) {
+            Ok((text, _)) => Ok(Self { file_path, text }),
             Err(e) => Err(anyhow!("Failed to read file: {}", e)),
         }
     }
 
+    /// Returns the lines in the range with tabs expanded to four spaces.
     pub fn lines(&self, range: Range<usize>) -> String {
+        self.raw_lines(range)
+            .into_iter()
+            .map(|line| line.replace('\t', TAB))
+            .join("\n")
+    }
+
+    pub fn raw_lines(&self, range: Range<usize>) -> Vec<&str> {
         self.text
             .lines()
             .skip(range.start)
             .take(range.end - range.start)
-            .join("\n")
+            .collect()
+    }
+
+    /// Maps a byte offset in the raw line to the line with tabs expanded.
+    pub fn expand_offset(raw_line: &str, offset: usize) -> usize {
+        offset
+            + raw_line
+                .get(..offset)
+                .map(|s| s.matches('\t').count() * (TAB.len() - 1))
+                .unwrap_or_default()
     }
 
     pub fn is_same_file(&self, file_path: String) -> bool {
         self.file_path == file_path
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("foo", 2, 2)]
+    #[case("\tfoo", 1, 4)]
+    #[case("\t\tfoo", 3, 9)]
+    #[case("foo\t", 10, 10)]
+    fn test_expand_offset(#[case] line: &str, #[case] offset: usize, #[case] expected: usize) {
+        assert_eq!(FilePreview::expand_offset(line, offset), expected);
+    }
+}
@@ -1,15 +1,18 @@
-use super::Line;
+use super::{FileStamp, Line, MatchResult, TextFile};
+use serde_json::{json, Value};
 use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct FileResult {
     pub file_path: String,
     pub lines: Vec<LineResult>,
+    pub stamp: Option<FileStamp>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum LineResult {
-    Line(Line),
+    Line(Arc<Line>),
     Separator,
 }
 
@@ -18,6 +21,14 @@ pub struct FileResultVimGrep {
     pub lines: Vec<LineResult>,
 }
 
+/// JSON Lines records in the style of ripgrep's `--json`: `begin`, `match` or `context` per line, then `end`.
+/// Offsets, terminators and text are the ones the lines had in the file when it was searched.
+/// Lines that a later condition filtered out are left out, as they are in the TUI.
+pub struct FileResultJson {
+    pub file_path: String,
+    pub lines: Vec<LineResult>,
+}
+
 impl Display for FileResult {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         if !self.file_path.is_empty() {
@@ -59,7 +70,107 @@ impl Display for FileResultVimGrep {
     }
 }
 
+impl Display for FileResultJson {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let path = json!({ "text": self.file_path });
+        let lines = self
+            .lines
+            .iter()
+            .filter_map(|l| l.line())
+            .filter(|l| !l.is_filtered())
+            .collect::<Vec<_>>();
+
+        writeln!(
+            f,
+            "{}",
+            json!({ "type": "begin", "data": { "path": path } })
+        )?;
+
+        for line in &lines {
+            writeln!(
+                f,
+                "{}",
+                json!({
+                    "type": if line.is_context() { "context" } else { "match" },
+                    "data": {
+                        "path": path,
+                        "lines": {
+                            "text": format!(
+                                "{}{}",
+                                line.text,
+                                line.position.map_or("\n", |p| p.terminator)
+                            )
+                        },
+                        "line_number": line.line_no,
+                        "absolute_offset": line.position.map(|p| p.offset),
+                        "submatches": line
+                            .matches()
+                            .iter()
+                            .map(|m| submatch(line, m))
+                            .collect::<Vec<_>>(),
+                        "transformed": if line.contains_transformed() {
+                            json!({ "text": line.apply_transforms(&line.text) })
+                        } else {
+                            Value::Null
+                        },
+                    }
+                })
+            )?;
+        }
+
+        writeln!(
+            f,
+            "{}",
+            json!({
+                "type": "end",
+                "data": {
+                    "path": path,
+                    "stats": {
+                        "matched_lines": lines.iter().filter(|l| !l.is_context()).count(),
+                        "matches": lines.iter().map(|l| l.count_matches()).sum::<usize>(),
+                    }
+                }
+            })
+        )
+    }
+}
+
+fn submatch(line: &Line, m: &MatchResult) -> Value {
+    let (kind, replacement) = match m {
+        MatchResult::Found(_, _, _, _) => ("found", Value::Null),
+        MatchResult::Filtered(_, _, _, _) => ("filtered", Value::Null),
+        MatchResult::Transformed(text, _, _, _) => ("transformed", json!({ "text": text })),
+    };
+
+    json!({
+        "match": { "text": line.text.get(m.range()) },
+        "replacement": replacement,
+        "kind": kind,
+        "start": m.range().start,
+        "end": m.range().end,
+        "index": m.index(),
+    })
+}
+
 impl FileResult {
+    pub fn display_json(&self) -> FileResultJson {
+        FileResultJson {
+            file_path: self.file_path.clone(),
+            lines: self.lines.clone(),
+        }
+    }
+
+    /// The number of found matches on the lines that are not filtered out, as counted in the `end` record
+    /// of `display_json`.
+    pub fn match_count(&self) -> usize {
+        self.lines
+            .iter()
+            .filter_map(|line| line.line())
+            .filter(|line| !line.is_filtered())
+            .map(|line| line.count_matches())
+            .sum()
+    }
+
     pub fn display_vimgrep(&self) -> FileResultVimGrep {
         FileResultVimGrep {
             file_path: self.file_path.clone(),
@@ -67,6 +178,13 @@ impl FileResult {
         }
     }
 
+    /// Whether the file was changed on disk since it was searched.
+    pub fn is_modified(&self, text: &TextFile) -> bool {
+        self.stamp.is_some_and(|stamp| {
+            stamp.conflicts_with(FileStamp::modified(&self.file_path), || text.to_string())
+        })
+    }
+
     pub fn contains_transformed(&self) -> bool {
         self.lines.iter().any(|line| match line {
             LineResult::Line(line) => line.contains_transformed(),
@@ -80,10 +198,101 @@ impl LineResult {
         matches!(self, LineResult::Line(_))
     }
 
-    pub fn line(&self) -> Option<Line> {
+    pub fn line(&self) -> Option<&Line> {
         match self {
-            LineResult::Line(line) => Some(line.clone()),
+            LineResult::Line(line) => Some(line),
             LineResult::Separator => None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Captures, Position};
+    use std::ops::Range;
+
+    #[test]
+    fn test_display_json() {
+        let file = FileResultJson {
+            file_path: "test".to_string(),
+            lines: vec![
+                LineResult::Line(Arc::new(
+                    Line::new(1, "a".to_string(), Vec::new(), false)
+                        .as_context()
+                        .with_position(Position {
+                            offset: 0,
+                            terminator: "\r\n",
+                        }),
+                )),
+                LineResult::Line(Arc::new(
+                    Line::new(
+                        2,
+                        "foo bar".to_string(),
+                        vec![
+                            MatchResult::Found(
+                                "foo".to_string(),
+                                Range { start: 0, end: 3 },
+                                1,
+                                Captures::default(),
+                            ),
+                            MatchResult::Transformed(
+                                "FOO".to_string(),
+                                Range { start: 0, end: 3 },
+                                2,
+                                Captures::default(),
+                            ),
+                        ],
+                        false,
+                    )
+                    .with_position(Position {
+                        offset: 3,
+                        terminator: "\n",
+                    }),
+                )),
+                LineResult::Line(Arc::new(Line::new(
+                    3,
+                    "baz".to_string(),
+                    vec![MatchResult::Transformed(
+                        "BAZ".to_string(),
+                        Range { start: 0, end: 3 },
+                        2,
+                        Captures::default(),
+                    )],
+                    false,
+                ))),
+                LineResult::Line(Arc::new(Line::new(
+                    4,
+                    "qux\tquux".to_string(),
+                    Vec::new(),
+                    false,
+                ))),
+                LineResult::Line(Arc::new(Line::new(
+                    5,
+                    "foo".to_string(),
+                    vec![MatchResult::Found(
+                        "foo".to_string(),
+                        Range { start: 0, end: 3 },
+                        1,
+                        Captures::default(),
+                    )],
+                    true,
+                ))),
+            ],
+        };
+
+        assert_eq!(
+            file.to_string(),
+            [
+                r#"{"data":{"path":{"text":"test"}},"type":"begin"}"#,
+                r#"{"data":{"absolute_offset":0,"line_number":1,"lines":{"text":"a\r\n"},"path":{"text":"test"},"submatches":[],"transformed":null},"type":"context"}"#,
+                r#"{"data":{"absolute_offset":3,"line_number":2,"lines":{"text":"foo bar\n"},"path":{"text":"test"},"submatches":[{"end":3,"index":1,"kind":"found","match":{"text":"foo"},"replacement":null,"start":0},{"end":3,"index":2,"kind":"transformed","match":{"text":"foo"},"replacement":{"text":"FOO"},"start":0}],"transformed":{"text":"FOO bar"}},"type":"match"}"#,
+                r#"{"data":{"absolute_offset":null,"line_number":3,"lines":{"text":"baz\n"},"path":{"text":"test"},"submatches":[{"end":3,"index":2,"kind":"transformed","match":{"text":"baz"},"replacement":{"text":"BAZ"},"start":0}],"transformed":{"text":"BAZ"}},"type":"match"}"#,
+                r#"{"data":{"absolute_offset":null,"line_number":4,"lines":{"text":"qux\tquux\n"},"path":{"text":"test"},"submatches":[],"transformed":null},"type":"match"}"#,
+                r#"{"data":{"path":{"text":"test"},"stats":{"matched_lines":3,"matches":1}},"type":"end"}"#,
+                "",
+            ]
+            .join("\n")
+        );
+    }
+}
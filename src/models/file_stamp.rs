@@ -0,0 +1,95 @@
+use std::fs;
+use std::hash::{DefaultHasher, Hasher};
+use std::time::SystemTime;
+
+/// The state of a file at search time, used to detect changes made before the replacements are written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FileStamp {
+    pub modified: Option<SystemTime>,
+    pub hash: u64,
+}
+
+impl FileStamp {
+    pub fn new(content: &str, modified: Option<SystemTime>) -> Self {
+        let mut hasher = StampHasher::default();
+        hasher.update(content);
+        hasher.finish(modified)
+    }
+
+    pub fn modified(path: &str) -> Option<SystemTime> {
+        fs::metadata(path).and_then(|m| m.modified()).ok()
+    }
+
+    /// A file with the same modification time is not hashed again. Otherwise only the content decides,
+    /// so a file that was saved without changes is not a conflict.
+    pub fn conflicts_with<F>(&self, modified: Option<SystemTime>, content: F) -> bool
+    where
+        F: FnOnce() -> String,
+    {
+        match (self.modified, modified) {
+            (Some(a), Some(b)) if a == b => false,
+            _ => self.hash != FileStamp::new(&content(), modified).hash,
+        }
+    }
+}
+
+/// Hashes content given in any number of pieces to the same stamp as `FileStamp::new`,
+/// so that a file can be stamped while it is streamed.
+#[derive(Clone, Debug, Default)]
+pub struct StampHasher(DefaultHasher);
+
+impl StampHasher {
+    pub fn update(&mut self, content: &str) {
+        self.0.write(content.as_bytes());
+    }
+
+    pub fn finish(self, modified: Option<SystemTime>) -> FileStamp {
+        FileStamp {
+            modified,
+            hash: self.0.finish(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use std::time::Duration;
+
+    #[rstest]
+    #[case("foo", Some(SystemTime::UNIX_EPOCH), false)]
+    #[case("foo", Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1)), false)]
+    #[case("bar", Some(SystemTime::UNIX_EPOCH), false)]
+    #[case("bar", Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1)), true)]
+    #[case("bar", None, true)]
+    fn test_conflicts_with(
+        #[case] content: &str,
+        #[case] modified: Option<SystemTime>,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(
+            FileStamp::new("foo", Some(SystemTime::UNIX_EPOCH))
+                .conflicts_with(modified, || content.to_string()),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(vec!["foo\n", "bar"], "foo\nbar")]
+    #[case(vec!["", "0123456789abcdef", "g"], "0123456789abcdefg")]
+    #[case(vec!["012", "3456", "789"], "0123456789")]
+    fn test_stamp_hasher(#[case] pieces: Vec<&str>, #[case] content: &str) {
+        let mut hasher = StampHasher::default();
+        pieces.iter().for_each(|piece| hasher.update(piece));
+
+        assert_eq!(hasher.finish(None), FileStamp::new(content, None));
+    }
+
+    #[rstest]
+    #[case("foo", "foo\0")]
+    #[case("foo bar", "foo baz")]
+    fn test_stamp_hasher_differs(#[case] a: &str, #[case] b: &str) {
+        assert_ne!(FileStamp::new(a, None), FileStamp::new(b, None));
+    }
+}
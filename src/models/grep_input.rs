@@ -0,0 +1,143 @@
+use clap::ValueEnum;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+/// How piped input is read: as text to search, or as the output of grep or `rg --json`
+/// that references lines in files.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, strum_macros::Display, ValueEnum)]
+#[strum(serialize_all = "kebab_case")]
+pub enum InputFormat {
+    #[default]
+    Auto,
+    Text,
+    Grep,
+    Json,
+}
+
+/// Line numbers referenced per file, in the order the files first appear.
+pub type GrepLines = Vec<(String, BTreeSet<usize>)>;
+
+/// Parses `path:line:text`, `path:line:column:text` or `rg --json` output.
+/// Returns `None` if the input should be searched as plain text.
+pub fn parse(input: &str, format: InputFormat) -> Option<GrepLines> {
+    let lines = input
+        .lines()
+        .filter(|line| !line.trim().is_empty() && *line != "--");
+
+    match format {
+        InputFormat::Text => None,
+        InputFormat::Grep => Some(group(lines.filter_map(|line| grep_line(line).next()))),
+        InputFormat::Json => Some(group(lines.filter_map(json_line).flatten())),
+        InputFormat::Auto => {
+            let lines = lines.collect::<Vec<_>>();
+
+            if lines.is_empty() {
+                None
+            } else if lines.iter().all(|line| line.starts_with('{')) {
+                lines
+                    .iter()
+                    .map(|line| json_line(line))
+                    .collect::<Option<Vec<_>>>()
+                    .map(|lines| group(lines.into_iter().flatten()))
+            } else {
+                lines
+                    .iter()
+                    .map(|line| grep_line(line).find(|(path, _)| Path::new(path).is_file()))
+                    .collect::<Option<Vec<_>>>()
+                    .map(|lines| group(lines.into_iter()))
+            }
+        }
+    }
+}
+
+/// Every way to read the line as `path:line:`, from the shortest path, since a path may contain ':'.
+fn grep_line(line: &str) -> impl Iterator<Item = (String, usize)> + '_ {
+    line.match_indices(':').filter_map(move |(i, _)| {
+        let (path, rest) = (&line[..i], &line[i + 1..]);
+        let (line_no, _) = rest.split_once(':')?;
+
+        match line_no.parse::<usize>() {
+            Ok(line_no) if !path.is_empty() && line_no > 0 => Some((path.to_string(), line_no)),
+            _ => None,
+        }
+    })
+}
+
+/// Returns the referenced line of a `match` record, `Some(None)` for other valid records.
+fn json_line(line: &str) -> Option<Option<(String, usize)>> {
+    let record = serde_json::from_str::<Value>(line).ok()?;
+
+    match record["type"].as_str()? {
+        "match" => Some(
+            record["data"]["path"]["text"]
+                .as_str()
+                .zip(record["data"]["line_number"].as_u64())
+                .map(|(path, line_no)| (path.to_string(), line_no as usize)),
+        ),
+        _ => Some(None),
+    }
+}
+
+fn group(lines: impl Iterator<Item = (String, usize)>) -> GrepLines {
+    let mut index = HashMap::new();
+    let mut files: GrepLines = Vec::new();
+
+    for (path, line_no) in lines {
+        let i = *index.entry(path.clone()).or_insert_with(|| {
+            files.push((path, BTreeSet::new()));
+            files.len() - 1
+        });
+        files[i].1.insert(line_no);
+    }
+
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("a.rs:1:foo\na.rs:3:10:bar\n--\nb.rs:2:baz", InputFormat::Grep, Some(vec![("a.rs", vec![1, 3]), ("b.rs", vec![2])]))]
+    #[case("a.rs:foo\nb.rs:2:baz", InputFormat::Grep, Some(vec![("b.rs", vec![2])]))]
+    #[case("a.rs:1:foo:2:bar\nc:d.rs:3:baz", InputFormat::Grep, Some(vec![("a.rs", vec![1]), ("c:d.rs", vec![3])]))]
+    #[case(r#"{"type":"begin","data":{"path":{"text":"a.rs"}}}
+{"type":"match","data":{"path":{"text":"a.rs"},"line_number":4}}
+{"type":"context","data":{"path":{"text":"a.rs"},"line_number":5}}"#, InputFormat::Json, Some(vec![("a.rs", vec![4])]))]
+    #[case(r#"{"type":"match","data":{"path":{"text":"Cargo.toml"},"line_number":1}}"#, InputFormat::Auto, Some(vec![("Cargo.toml", vec![1])]))]
+    #[case("Cargo.toml:2:foo\nsrc/main.rs:1:1:bar", InputFormat::Auto, Some(vec![("Cargo.toml", vec![2]), ("src/main.rs", vec![1])]))]
+    #[case("not_exists.rs:2:foo", InputFormat::Auto, None)]
+    #[case("foo bar\nbaz", InputFormat::Auto, None)]
+    #[case("", InputFormat::Auto, None)]
+    #[case("Cargo.toml:2:foo", InputFormat::Text, None)]
+    fn test_parse(
+        #[case] input: &str,
+        #[case] format: InputFormat,
+        #[case] expected: Option<Vec<(&str, Vec<usize>)>>,
+    ) {
+        assert_eq!(
+            parse(input, format),
+            expected.map(|files| files
+                .into_iter()
+                .map(|(path, lines)| (path.to_string(), lines.into_iter().collect()))
+                .collect())
+        );
+    }
+
+    #[test]
+    fn test_parse_path_with_colons() {
+        let dir = std::env::temp_dir().join(format!("igr-grep-input-{}", std::process::id()));
+        let path = dir.join("a:1:b.rs");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&path, "foo\nbar").unwrap();
+        let path = path.to_str().unwrap().to_string();
+
+        assert_eq!(
+            parse(&format!("{}:2:bar", path), InputFormat::Auto),
+            Some(vec![(path, BTreeSet::from([2]))])
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,153 @@
+use super::config::data_dir;
+use super::text_file::write_atomic;
+use anyhow::Result;
+use itertools::Itertools;
+use std::fs;
+use std::path::PathBuf;
+
+const HISTORY_FILE: &str = "history";
+const MAX_ENTRIES: usize = 1000;
+
+/// Executed queries, oldest first, stored one per line in the data directory.
+#[derive(Clone, Debug, Default)]
+pub struct History {
+    path: Option<PathBuf>,
+    entries: Vec<String>,
+}
+
+impl History {
+    pub fn load() -> Self {
+        Self::open(data_dir().map(|dir| dir.join(HISTORY_FILE)))
+    }
+
+    pub fn open(path: Option<PathBuf>) -> Self {
+        let entries = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|text| {
+                text.lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(|line| line.to_string())
+                    .collect_vec()
+            })
+            .unwrap_or_default();
+
+        Self { path, entries }
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// Appends the query, moving it to the end if it already exists.
+    /// A query that extends the last one with more stages replaces it.
+    pub fn push(&mut self, query: &str) -> Result<()> {
+        let query = query.trim();
+
+        if query.is_empty() || query.contains('\n') {
+            return Ok(());
+        }
+
+        if self
+            .entries
+            .last()
+            .is_some_and(|last| query.starts_with(&format!("{} | ", last)))
+        {
+            self.entries.pop();
+        }
+
+        self.entries.retain(|entry| entry != query);
+        self.entries.push(query.to_string());
+
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.drain(..self.entries.len() - MAX_ENTRIES);
+        }
+
+        self.save()
+    }
+
+    /// Returns the entries containing the characters of `pattern` in order, newest first.
+    pub fn search(&self, pattern: &str) -> Vec<&str> {
+        let pattern = pattern.to_lowercase();
+
+        self.entries
+            .iter()
+            .rev()
+            .filter(|entry| {
+                let mut chars = entry.to_lowercase().chars().collect_vec().into_iter();
+                pattern.chars().all(|c| chars.any(|e| e == c))
+            })
+            .map(|entry| entry.as_str())
+            .collect_vec()
+    }
+
+    fn save(&self) -> Result<()> {
+        match &self.path {
+            Some(path) => {
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+
+                write_atomic(path, format!("{}\n", self.entries.join("\n")).as_bytes())
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(vec!["foo", "bar"], "baz", vec!["foo", "bar", "baz"])]
+    #[case(vec!["foo", "bar"], "foo", vec!["bar", "foo"])]
+    #[case(vec!["foo", "bar"], "bar | upper_case()", vec!["foo", "bar | upper_case()"])]
+    #[case(vec!["bar", "foo"], "bar | upper_case()", vec!["bar", "foo", "bar | upper_case()"])]
+    #[case(vec!["foo"], "  ", vec!["foo"])]
+    fn test_push(#[case] entries: Vec<&str>, #[case] query: &str, #[case] expected: Vec<&str>) {
+        let mut history = History {
+            path: None,
+            entries: entries.into_iter().map(|e| e.to_string()).collect(),
+        };
+
+        history.push(query).unwrap();
+        assert_eq!(history.entries(), expected);
+    }
+
+    #[rstest]
+    #[case("", vec!["todo | upper_case()", "fixme", "todo"])]
+    #[case("tdup", vec!["todo | upper_case()"])]
+    #[case("FIX", vec!["fixme"])]
+    #[case("xyz", vec![])]
+    fn test_search(#[case] pattern: &str, #[case] expected: Vec<&str>) {
+        let history = History {
+            path: None,
+            entries: vec![
+                "todo".to_string(),
+                "fixme".to_string(),
+                "todo | upper_case()".to_string(),
+            ],
+        };
+
+        assert_eq!(history.search(pattern), expected);
+    }
+
+    #[test]
+    fn test_save() {
+        let path = std::env::temp_dir()
+            .join(format!("igr-history-{}", std::process::id()))
+            .join(HISTORY_FILE);
+        let mut history = History::open(Some(path.clone()));
+
+        history.push("foo").unwrap();
+        history.push("foo | upper_case()").unwrap();
+
+        assert_eq!(
+            History::open(Some(path.clone())).entries(),
+            ["foo | upper_case()"]
+        );
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
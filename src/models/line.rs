@@ -14,6 +14,17 @@ pub struct Line {
     pub text: String,
     matches: Vec<MatchResult>,
     filtered: bool,
+    /// Whether the line was collected as context around a match rather than selected by the conditions.
+    context: bool,
+    /// Where the line is in the file, if it was read from one.
+    pub position: Option<Position>,
+}
+
+/// The byte offset of a line in its file, in the file's encoding, and the terminator that ends it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Position {
+    pub offset: usize,
+    pub terminator: &'static str,
 }
 
 impl Line {
@@ -29,6 +40,31 @@ impl Line {
             text,
             matches,
             filtered,
+            context: false,
+            position: None,
+        }
+    }
+
+    /// The same line with other matches, keeping its position in the file.
+    pub fn with_matches(&self, matches: Vec<MatchResult>, filtered: bool) -> Line {
+        Line {
+            context: self.context,
+            position: self.position,
+            ..Line::new(self.line_no, self.text.clone(), matches, filtered)
+        }
+    }
+
+    pub fn as_context(self) -> Line {
+        Line {
+            context: true,
+            ..self
+        }
+    }
+
+    pub fn with_position(self, position: Position) -> Line {
+        Line {
+            position: Some(position),
+            ..self
         }
     }
 
@@ -36,6 +72,10 @@ impl Line {
         self.filtered
     }
 
+    pub fn is_context(&self) -> bool {
+        self.context
+    }
+
     pub fn matches(&self) -> &Vec<MatchResult> {
         &self.matches
     }
@@ -52,12 +92,31 @@ impl Line {
         self.matches.iter().any(|m| m.is_transformed())
     }
 
+    /// Builds the transformed line from `text`, the line as it is in the file, copying the text
+    /// between the transformed ranges. A range that overlaps an earlier one is left out.
+    pub fn apply_transforms(&self, text: &str) -> String {
+        let mut applied = String::with_capacity(text.len());
+        let end = self.matches.iter().fold(0, |start, m| match m {
+            MatchResult::Transformed(token, range, _, _) if range.start >= start => {
+                applied.push_str(&text[start..range.start]);
+                applied.push_str(token);
+                range.end
+            }
+            _ => start,
+        });
+
+        applied.push_str(&text[end..]);
+        applied
+    }
+
     pub fn filter(&self, filter: Arc<dyn Filter>) -> Line {
         Line {
             line_no: self.line_no,
             text: self.text.clone(),
             matches: self.matches.clone(),
             filtered: !filter.filter(&self.text),
+            context: self.context,
+            position: self.position,
         }
     }
 
@@ -67,6 +126,8 @@ impl Line {
             text: self.text.clone(),
             matches: self.matches.clone(),
             filtered,
+            context: self.context,
+            position: self.position,
         }
     }
 
@@ -74,6 +135,7 @@ impl Line {
         self.matches.iter().filter(|m| m.is_found()).count()
     }
 
+    /// Splits the line into the text between matches and the matches, with tabs shown as spaces.
     pub fn tokens(&self) -> Vec<(String, Option<MatchResult>)> {
         let mut current_index = 0;
         let mut tokens: Vec<(String, Option<MatchResult>)> = vec![];
@@ -81,7 +143,7 @@ impl Line {
 
         for m in matches {
             match m.clone() {
-                MatchResult::Found(_, range, _) => {
+                MatchResult::Found(_, range, _, _) => {
                     if current_index < range.start {
                         tokens.push((self.text[current_index..range.start].to_string(), None));
                     }
@@ -94,14 +156,14 @@ impl Line {
 
                     current_index = range.end;
                 }
-                MatchResult::Filtered(_, range, _) => {
+                MatchResult::Filtered(_, range, _, _) => {
                     if current_index < range.start {
                         tokens.push((self.text[current_index..range.start].to_string(), None));
                     }
                     tokens.push((self.text[range.start..range.end].to_string(), Some(m)));
                     current_index = range.end;
                 }
-                MatchResult::Transformed(_, range, _) => {
+                MatchResult::Transformed(_, range, _, _) => {
                     if current_index < range.start {
                         tokens.push((self.text[current_index..range.start].to_string(), None));
                     }
@@ -116,7 +178,11 @@ impl Line {
         } else if !self.text.is_empty() && current_index < self.text.len() {
             tokens.push((self.text[current_index..self.text.len()].to_string(), None));
         }
+
         tokens
+            .into_iter()
+            .map(|(token, m)| (token.replace('\t', " "), m))
+            .collect()
     }
 }
 
@@ -124,13 +190,13 @@ impl Display for Line {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(f, "{}:", self.line_no)?;
         for c in self.tokens().into_iter().flat_map(|(token, m)| match m {
-            Some(MatchResult::Found(_, _, index)) => {
+            Some(MatchResult::Found(_, _, index, _)) => {
                 vec![token
                     .to_string()
                     .color(MatchColors::get_cli_color(index))
                     .bold()]
             }
-            Some(MatchResult::Transformed(text, _, index)) => {
+            Some(MatchResult::Transformed(text, _, index, _)) => {
                 vec![
                     token.to_string().strikethrough(),
                     text.to_string()
@@ -150,19 +216,22 @@ impl Display for Line {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{filter::InvertMatch, models::MatchResult};
+    use crate::{
+        filter::InvertMatch,
+        models::{Captures, MatchResult},
+    };
     use rstest::rstest;
     use std::{ops::Range, vec};
 
     #[rstest]
-    #[case(vec![MatchResult::Found("string".to_string(), Range{start: 5, end: 11}, 1),
-                MatchResult::Transformed("test".to_string(), Range{start: 0, end: 4}, 2),
-                MatchResult::Filtered("test".to_string(), Range{start: 0, end: 4}, 3),
-                MatchResult::Found("test".to_string(), Range{start: 0, end: 4}, 1)],
-           vec![MatchResult::Found("test".to_string(), Range{start: 0, end: 4}, 1),
-                MatchResult::Transformed("test".to_string(), Range{start: 0, end: 4}, 2),
-                MatchResult::Filtered("test".to_string(), Range{start: 0, end: 4}, 3),
-                MatchResult::Found("string".to_string(), Range{start: 5, end: 11}, 1),
+    #[case(vec![MatchResult::Found("string".to_string(), Range{start: 5, end: 11}, 1, Captures::default()),
+                MatchResult::Transformed("test".to_string(), Range{start: 0, end: 4}, 2, Captures::default()),
+                MatchResult::Filtered("test".to_string(), Range{start: 0, end: 4}, 3, Captures::default()),
+                MatchResult::Found("test".to_string(), Range{start: 0, end: 4}, 1, Captures::default())],
+           vec![MatchResult::Found("test".to_string(), Range{start: 0, end: 4}, 1, Captures::default()),
+                MatchResult::Transformed("test".to_string(), Range{start: 0, end: 4}, 2, Captures::default()),
+                MatchResult::Filtered("test".to_string(), Range{start: 0, end: 4}, 3, Captures::default()),
+                MatchResult::Found("string".to_string(), Range{start: 5, end: 11}, 1, Captures::default()),
            ])]
     fn test_new(#[case] matches: Vec<MatchResult>, #[case] expected: Vec<MatchResult>) {
         assert_eq!(
@@ -177,13 +246,15 @@ mod tests {
     #[case(Line {
             line_no: 1,
             text: "test string end😄".to_string(),
-            matches: vec![MatchResult::Found("test".to_string(), Range{start: 0, end: 4}, 1),
-                          MatchResult::Found("string".to_string(), Range{start: 5, end: 11}, 1)],
-            filtered: false},
+            matches: vec![MatchResult::Found("test".to_string(), Range{start: 0, end: 4}, 1, Captures::default()),
+                          MatchResult::Found("string".to_string(), Range{start: 5, end: 11}, 1, Captures::default())],
+            filtered: false,
+            context: false,
+            position: None},
             vec![("test".to_string(),
-                  Some(MatchResult::Found("test".to_string(), Range{start: 0, end: 4}, 1))),
+                  Some(MatchResult::Found("test".to_string(), Range{start: 0, end: 4}, 1, Captures::default()))),
                   (" ".to_string(), None),
-                  ("string".to_string(), Some(MatchResult::Found("string".to_string(), Range{start: 5, end: 11}, 1))),
+                  ("string".to_string(), Some(MatchResult::Found("string".to_string(), Range{start: 5, end: 11}, 1, Captures::default()))),
                   (" end😄".to_string(), None),
                 ])]
     fn test_tokens(#[case] line: Line, #[case] expected: Vec<(String, Option<MatchResult>)>) {
@@ -194,9 +265,11 @@ mod tests {
     #[case(Line {
             line_no: 1,
             text: "test string".to_string(),
-            matches: vec![MatchResult::Found("test".to_string(), Range{start: 0, end: 4}, 1),
-                          MatchResult::Found("string".to_string(), Range{start: 5, end: 11}, 1)],
-            filtered: false},
+            matches: vec![MatchResult::Found("test".to_string(), Range{start: 0, end: 4}, 1, Captures::default()),
+                          MatchResult::Found("string".to_string(), Range{start: 5, end: 11}, 1, Captures::default())],
+            filtered: false,
+            context: false,
+            position: None},
             InvertMatch::new("string".to_string()),
             true)]
     fn test_filter(#[case] line: Line, #[case] filter: Arc<dyn Filter>, #[case] expected: bool) {
@@ -0,0 +1,210 @@
+use super::decoder::{ReadError, Skip};
+use encoding_rs::{DecoderResult, Encoding};
+use std::io::{self, Read};
+use std::ops::Range;
+
+const CHUNK_LEN: usize = 64 * 1024;
+
+/// Returns the line without its `\n` or `\r\n` terminator.
+pub fn trim_terminator(line: &str) -> &str {
+    line.strip_suffix('\n')
+        .map(|l| l.strip_suffix('\r').unwrap_or(l))
+        .unwrap_or(line)
+}
+
+/// Returns the `\n` or `\r\n` terminator of the line, or an empty string if it has none.
+pub fn terminator(line: &str) -> &'static str {
+    if line.ends_with("\r\n") {
+        "\r\n"
+    } else if line.ends_with('\n') {
+        "\n"
+    } else {
+        ""
+    }
+}
+
+/// Decodes a reader chunk by chunk and yields its lines with their terminators, one by one or in blocks,
+/// so that only the current chunk and the line being read are held in memory.
+pub struct LineReader<R> {
+    reader: R,
+    encoding: &'static Encoding,
+    decoder: encoding_rs::Decoder,
+    chunk: Vec<u8>,
+    text: String,
+    /// The start of the next line in `text`.
+    start: usize,
+    /// Where to continue looking for the end of the line, as `text[start..searched]` has no newline.
+    searched: usize,
+    eof: bool,
+}
+
+impl<R: Read> LineReader<R> {
+    /// `prefix` is yielded before the decoded text, e.g. the BOM that was already consumed.
+    pub fn new(reader: R, encoding: &'static Encoding, prefix: &str) -> Self {
+        Self {
+            reader,
+            encoding,
+            decoder: encoding.new_decoder_without_bom_handling(),
+            chunk: vec![0; CHUNK_LEN],
+            text: prefix.to_string(),
+            start: 0,
+            searched: 0,
+            eof: false,
+        }
+    }
+
+    pub fn encoding(&self) -> &'static Encoding {
+        self.encoding
+    }
+
+    fn fill(&mut self) -> Result<(), ReadError> {
+        self.text.drain(..self.start);
+        self.searched -= self.start;
+        self.start = 0;
+
+        let len = loop {
+            match self.reader.read(&mut self.chunk) {
+                Ok(len) => break len,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(ReadError::Io(e)),
+            }
+        };
+        self.eof = len == 0;
+
+        let mut bytes = &self.chunk[..len];
+
+        loop {
+            self.text.reserve(
+                self.decoder
+                    .max_utf8_buffer_length_without_replacement(bytes.len())
+                    .unwrap_or(bytes.len()),
+            );
+
+            match self
+                .decoder
+                .decode_to_string_without_replacement(bytes, &mut self.text, self.eof)
+            {
+                (DecoderResult::InputEmpty, _) => return Ok(()),
+                (DecoderResult::OutputFull, read) => bytes = &bytes[read..],
+                (DecoderResult::Malformed(_, _), _) => {
+                    return Err(ReadError::Skip(Skip::Undecodable(self.encoding)))
+                }
+            }
+        }
+    }
+
+    /// Returns the whole lines decoded so far, each with its terminator except the last line of the input.
+    pub fn next_block(&mut self) -> Option<Result<&str, ReadError>> {
+        match self.next_range(|text| text.rfind('\n')) {
+            Ok(range) => range.map(|range| Ok(&self.text[range])),
+            Err(e) => Some(Err(self.fuse(e))),
+        }
+    }
+
+    /// Returns the range of the text up to the newline that `find` returns,
+    /// or of the rest of the text at the end of the input.
+    fn next_range(
+        &mut self,
+        find: impl Fn(&str) -> Option<usize>,
+    ) -> Result<Option<Range<usize>>, ReadError> {
+        loop {
+            if let Some(i) = find(&self.text[self.searched..]) {
+                let end = self.searched + i + 1;
+                let range = self.start..end;
+
+                self.start = end;
+                self.searched = end;
+                return Ok(Some(range));
+            }
+
+            self.searched = self.text.len();
+
+            if self.eof {
+                let range = self.start..self.text.len();
+
+                self.start = self.text.len();
+                return Ok((!range.is_empty()).then_some(range));
+            }
+
+            self.fill()?;
+        }
+    }
+
+    fn fuse(&mut self, e: ReadError) -> ReadError {
+        self.eof = true;
+        self.start = self.text.len();
+        self.searched = self.text.len();
+        e
+    }
+}
+
+impl<R: Read> Iterator for LineReader<R> {
+    type Item = Result<String, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_range(|text| text.find('\n')) {
+            Ok(range) => range.map(|range| Ok(self.text[range].to_string())),
+            Err(e) => Some(Err(self.fuse(e))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encoding_rs::{UTF_16LE, UTF_8, WINDOWS_1252};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(b"foo\nbar\r\nbaz".to_vec(), UTF_8, "", vec!["foo\n", "bar\r\n", "baz"])]
+    #[case(b"foo\n\n".to_vec(), UTF_8, "", vec!["foo\n", "\n"])]
+    #[case(b"foo".to_vec(), UTF_8, "\u{feff}", vec!["\u{feff}foo"])]
+    #[case(b"".to_vec(), UTF_8, "", Vec::<&str>::new())]
+    #[case(b"caf\xe9\n".to_vec(), WINDOWS_1252, "", vec!["café\n"])]
+    #[case(b"f\x00\n\x00o\x00".to_vec(), UTF_16LE, "", vec!["f\n", "o"])]
+    #[case(format!("{}\n{}", "a".repeat(CHUNK_LEN + 1), "é".repeat(CHUNK_LEN)).into_bytes(), UTF_8, "", vec![format!("{}\n", "a".repeat(CHUNK_LEN + 1)), "é".repeat(CHUNK_LEN)])]
+    fn test_lines(
+        #[case] bytes: Vec<u8>,
+        #[case] encoding: &'static Encoding,
+        #[case] prefix: &str,
+        #[case] expected: Vec<impl Into<String>>,
+    ) {
+        assert_eq!(
+            LineReader::new(bytes.as_slice(), encoding, prefix)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap(),
+            expected
+                .into_iter()
+                .map(Into::into)
+                .collect::<Vec<String>>()
+        );
+    }
+
+    #[rstest]
+    #[case(b"foo\nbar\nba".to_vec(), vec!["foo\nbar\n", "ba"])]
+    #[case(format!("{}\n{}", "a".repeat(CHUNK_LEN - 1), "b".repeat(CHUNK_LEN)).into_bytes(), vec![format!("{}\n", "a".repeat(CHUNK_LEN - 1)), "b".repeat(CHUNK_LEN)])]
+    fn test_blocks(#[case] bytes: Vec<u8>, #[case] expected: Vec<impl Into<String>>) {
+        let mut reader = LineReader::new(bytes.as_slice(), UTF_8, "");
+        let mut blocks = Vec::new();
+
+        while let Some(block) = reader.next_block() {
+            blocks.push(block.unwrap().to_string());
+        }
+
+        assert_eq!(
+            blocks,
+            expected
+                .into_iter()
+                .map(Into::into)
+                .collect::<Vec<String>>()
+        );
+    }
+
+    #[test]
+    fn test_lines_undecodable() {
+        assert!(matches!(
+            LineReader::new(&b"foo\ncaf\xe9\n"[..], UTF_8, "").collect::<Result<Vec<_>, _>>(),
+            Err(ReadError::Skip(Skip::Undecodable(e))) if e == UTF_8
+        ));
+    }
+}
@@ -1,3 +1,4 @@
+use super::Captures;
 use crate::{filter::Filter, matcher::Matcher, transform::Transform};
 use std::fmt;
 use std::fmt::Display;
@@ -6,17 +7,17 @@ use std::sync::Arc;
 
 #[derive(Clone, Debug, Eq)]
 pub enum MatchResult {
-    Found(String, Range<usize>, usize),
-    Filtered(String, Range<usize>, usize),
-    Transformed(String, Range<usize>, usize),
+    Found(String, Range<usize>, usize, Captures),
+    Filtered(String, Range<usize>, usize, Captures),
+    Transformed(String, Range<usize>, usize, Captures),
 }
 
 impl Display for MatchResult {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Self::Found(text, r, _) => write!(f, "found_{}_{}_{}", text, r.start, r.end),
-            Self::Filtered(text, r, _) => write!(f, "filtered_{}_{}_{}", text, r.start, r.end),
-            Self::Transformed(text, r, _) => {
+            Self::Found(text, r, _, _) => write!(f, "found_{}_{}_{}", text, r.start, r.end),
+            Self::Filtered(text, r, _, _) => write!(f, "filtered_{}_{}_{}", text, r.start, r.end),
+            Self::Transformed(text, r, _, _) => {
                 write!(f, "transformed_{}_{}_{}", text, r.start, r.end)
             }
         }
@@ -40,63 +41,86 @@ impl PartialOrd for MatchResult {
 
 impl MatchResult {
     pub fn is_found(&self) -> bool {
-        matches!(self, MatchResult::Found(_, _, _))
+        matches!(self, MatchResult::Found(_, _, _, _))
     }
 
     pub fn is_transformed(&self) -> bool {
-        matches!(self, MatchResult::Transformed(_, _, _))
+        matches!(self, MatchResult::Transformed(_, _, _, _))
     }
 
     pub fn index(&self) -> usize {
         match self {
-            Self::Found(_, _, i) => *i,
-            Self::Filtered(_, _, i) => *i,
-            Self::Transformed(_, _, i) => *i,
+            Self::Found(_, _, i, _) => *i,
+            Self::Filtered(_, _, i, _) => *i,
+            Self::Transformed(_, _, i, _) => *i,
         }
     }
 
     pub fn range(&self) -> Range<usize> {
         match self {
-            Self::Found(_, range, _) => range.clone(),
-            Self::Filtered(_, range, _) => range.clone(),
-            Self::Transformed(_, range, _) => range.clone(),
+            Self::Found(_, range, _, _) => range.clone(),
+            Self::Filtered(_, range, _, _) => range.clone(),
+            Self::Transformed(_, range, _, _) => range.clone(),
         }
     }
 
     pub fn column(&self) -> usize {
         match self {
-            Self::Found(_, range, _) => range.start,
-            Self::Filtered(_, range, _) => range.start,
-            Self::Transformed(_, range, _) => range.start,
+            Self::Found(_, range, _, _) => range.start,
+            Self::Filtered(_, range, _, _) => range.start,
+            Self::Transformed(_, range, _, _) => range.start,
+        }
+    }
+
+    pub fn captures(&self) -> &Captures {
+        match self {
+            Self::Found(_, _, _, captures) => captures,
+            Self::Filtered(_, _, _, captures) => captures,
+            Self::Transformed(_, _, _, captures) => captures,
         }
     }
 
     pub fn find(text: String, f: Arc<dyn Matcher>, index: usize) -> Vec<MatchResult> {
-        f.find(&text)
+        f.find_captures(&text)
             .into_iter()
-            .map(|(t, r)| Self::Found(t.to_string(), r, index))
+            .map(|(t, r, c)| Self::Found(t.to_string(), r, index, c))
             .collect()
     }
 
     pub fn filter(&self, filter: Arc<dyn Filter>, index: usize) -> Option<MatchResult> {
         match &self {
-            Self::Found(text, range, _) => {
+            Self::Found(text, range, _, captures) => {
                 if filter.filter(text) {
-                    Some(Self::Filtered(text.to_string(), range.clone(), index))
+                    Some(Self::Filtered(
+                        text.to_string(),
+                        range.clone(),
+                        index,
+                        captures.clone(),
+                    ))
                 } else {
                     None
                 }
             }
-            Self::Filtered(text, range, _) => {
+            Self::Filtered(text, range, _, captures) => {
                 if filter.filter(text) {
-                    Some(Self::Filtered(text.to_string(), range.clone(), index))
+                    Some(Self::Filtered(
+                        text.to_string(),
+                        range.clone(),
+                        index,
+                        captures.clone(),
+                    ))
                 } else {
                     None
                 }
             }
-            Self::Transformed(text, range, _) => {
+            Self::Transformed(text, range, _, captures) => {
                 if filter.filter(text) {
-                    Some(Self::Filtered(text.to_string(), range.clone(), index))
+                    Some(Self::Filtered(
+                        text.to_string(),
+                        range.clone(),
+                        index,
+                        captures.clone(),
+                    ))
                 } else {
                     None
                 }
@@ -106,10 +130,10 @@ impl MatchResult {
 
     pub fn transform(&self, transform: Arc<dyn Transform>, index: usize) -> Vec<MatchResult> {
         match &self {
-            Self::Found(text, range, _)
-            | Self::Filtered(text, range, _)
-            | Self::Transformed(text, range, _) => transform
-                .transform(text)
+            Self::Found(text, range, _, captures)
+            | Self::Filtered(text, range, _, captures)
+            | Self::Transformed(text, range, _, captures) => transform
+                .transform_captures(text, captures)
                 .into_iter()
                 .map(|(v, r)| {
                     Self::Transformed(
@@ -119,6 +143,7 @@ impl MatchResult {
                             end: range.start + r.end,
                         },
                         index,
+                        captures.clone(),
                     )
                 })
                 .collect(),
@@ -127,7 +152,7 @@ impl MatchResult {
 
     pub fn apply(&self, text: String) -> String {
         match &self {
-            Self::Transformed(token, range, _) => {
+            Self::Transformed(token, range, _, _) => {
                 format!("{}{}{}", &text[..range.start], token, &text[range.end..])
             }
             _ => text.to_string(),
@@ -142,11 +167,11 @@ mod tests {
     use std::vec;
 
     #[rstest]
-    #[case(vec![MatchResult::Transformed("transform".to_string(), Range{start: 0, end: 4}, 1)], "test_string".to_string(), "transform_string".to_string())]
-    #[case(vec![MatchResult::Transformed("transform".to_string(), Range{start: 0, end: 4}, 1),
-                MatchResult::Transformed("test".to_string(), Range{start: 0, end: 4}, 1)], "test_string".to_string(), "testsform_string".to_string())]
-    #[case(vec![MatchResult::Found("trqansform".to_string(), Range{start: 0, end: 4}, 1)], "test_string".to_string(), "test_string".to_string())]
-    #[case(vec![MatchResult::Filtered("trqansform".to_string(), Range{start: 0, end: 4}, 1)], "test_string".to_string(), "test_string".to_string())]
+    #[case(vec![MatchResult::Transformed("transform".to_string(), Range{start: 0, end: 4}, 1, Captures::default())], "test_string".to_string(), "transform_string".to_string())]
+    #[case(vec![MatchResult::Transformed("transform".to_string(), Range{start: 0, end: 4}, 1, Captures::default()),
+                MatchResult::Transformed("test".to_string(), Range{start: 0, end: 4}, 1, Captures::default())], "test_string".to_string(), "testsform_string".to_string())]
+    #[case(vec![MatchResult::Found("trqansform".to_string(), Range{start: 0, end: 4}, 1, Captures::default())], "test_string".to_string(), "test_string".to_string())]
+    #[case(vec![MatchResult::Filtered("trqansform".to_string(), Range{start: 0, end: 4}, 1, Captures::default())], "test_string".to_string(), "test_string".to_string())]
     fn apply_match_test(
         #[case] m: Vec<MatchResult>,
         #[case] text: String,
@@ -154,4 +179,29 @@ mod tests {
     ) {
         assert_eq!(m.iter().fold(text, |acc, x| x.apply(acc)), expected)
     }
+
+    #[test]
+    fn transform_captures_test() {
+        let re = regex::Regex::new(r"foo\((\w+)\)").unwrap();
+        let captures = Captures::new(&re, &re.captures("foo(bar)").unwrap());
+        let m = MatchResult::Found(
+            "foo(bar)".to_string(),
+            Range { start: 2, end: 10 },
+            1,
+            captures.clone(),
+        );
+
+        assert_eq!(
+            m.transform(
+                crate::transform::Update::new("baz($1, None)".to_string()),
+                2
+            ),
+            vec![MatchResult::Transformed(
+                "baz(bar, None)".to_string(),
+                Range { start: 2, end: 10 },
+                2,
+                captures
+            )]
+        )
+    }
 }
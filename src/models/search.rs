@@ -1,14 +1,25 @@
+mod scanner;
+
+use self::scanner::Scanner;
+use super::decoder::{ReadError, Skip};
 use super::file_result::LineResult;
-use super::{FileResult, Line, MatchResult, SearchCondition, SearchConfig};
+use super::file_stamp::StampHasher;
+use super::grep_input::{self, GrepLines};
+use super::{CancellationToken, FileResult, SearchCondition, SearchConfig, WalkFilter};
 use crate::models::search_result::SearchResult;
+use anyhow::Result;
 use colored::Colorize;
-use ignore::overrides::OverrideBuilder;
+use encoding_rs::UTF_8;
 use ignore::WalkBuilder;
 use itertools::Itertools;
 use rayon::prelude::*;
+use std::collections::BTreeSet;
 use std::fs;
 use std::path::Path;
 
+/// A scanned file: its result if it matched, or the reason it was skipped.
+type Scanned = Result<FileResult, (String, Skip)>;
+
 #[derive(Clone)]
 pub struct Search {
     path_list: Vec<String>,
@@ -20,51 +31,213 @@ impl Search {
         Self { path_list, stdin }
     }
 
-    pub fn search(&self, config: SearchConfig, conditions: Vec<SearchCondition>) -> SearchResult {
-        match self.stdin.clone() {
-            Some(_) => self.search_stdin(config, conditions),
-            None => self.search_files(config, conditions),
+    pub fn search(
+        &self,
+        config: SearchConfig,
+        conditions: Vec<SearchCondition>,
+    ) -> Result<SearchResult> {
+        self.search_with_progress(config, conditions, &CancellationToken::default(), |_| ())
+    }
+
+    /// Searches like `search`, calling `on_file` as soon as each file has been scanned,
+    /// with its result if the file matched. Files left when `token` is cancelled are skipped.
+    pub fn search_with_progress<F>(
+        &self,
+        config: SearchConfig,
+        conditions: Vec<SearchCondition>,
+        token: &CancellationToken,
+        on_file: F,
+    ) -> Result<SearchResult>
+    where
+        F: Fn(Option<&FileResult>) + Sync,
+    {
+        let grep_lines = self
+            .stdin
+            .as_deref()
+            .and_then(|stdin| grep_input::parse(stdin, config.input_format));
+
+        match (grep_lines, &self.stdin) {
+            (Some(grep_lines), _) => {
+                Ok(self.search_grep_lines(config, conditions, grep_lines, token, &on_file))
+            }
+            (None, Some(_)) => {
+                let result = self.search_stdin(config, conditions);
+                result.files.iter().for_each(|f| on_file(Some(f)));
+                Ok(result)
+            }
+            (None, None) => self.search_files(config, conditions, token, &on_file),
         }
     }
 
-    fn search_files(&self, config: SearchConfig, conditions: Vec<SearchCondition>) -> SearchResult {
+    fn search_files<F>(
+        &self,
+        config: SearchConfig,
+        conditions: Vec<SearchCondition>,
+        token: &CancellationToken,
+        on_file: &F,
+    ) -> Result<SearchResult>
+    where
+        F: Fn(Option<&FileResult>) + Sync,
+    {
         if conditions.is_empty() {
-            return SearchResult::new(vec![], vec![]);
+            return Ok(SearchResult::new(vec![], vec![]));
         }
 
         let find = conditions.iter().find(|c| c.is_matcher());
         let line_filter = conditions.iter().find(|c| c.is_line_filter());
+        let filter = WalkFilter::new(&config)?;
 
-        SearchResult::new(
+        Ok(Self::collect(
             self.path_list
                 .iter()
                 .flat_map(|path| {
-                    self.entries(path, &config)
+                    self.entries(path, &config, &filter, token)
                         .par_iter()
                         .filter_map(|path| {
-                            fs::read_to_string(path)
-                                .ok()
-                                .map(|content| {
-                                    self.search_content(content, &config, find, line_filter)
-                                })
-                                .and_then(|lines| {
-                                    if lines.is_empty() {
-                                        None
-                                    } else {
-                                        Some(FileResult {
-                                            file_path: path.to_string(),
-                                            lines,
-                                        })
-                                    }
-                                })
+                            if token.is_cancelled() {
+                                return None;
+                            }
+
+                            if !Self::is_selected(path, &config) {
+                                on_file(None);
+                                return None;
+                            }
+
+                            self.scan_file(path, &config, find, line_filter, None, on_file)
                         })
-                        .collect::<Vec<FileResult>>()
+                        .collect::<Vec<_>>()
                 })
-                .collect::<Vec<FileResult>>(),
+                .collect(),
+            conditions,
+        ))
+    }
+
+    /// Searches only the lines referenced by grep output read from stdin.
+    fn search_grep_lines<F>(
+        &self,
+        config: SearchConfig,
+        conditions: Vec<SearchCondition>,
+        grep_lines: GrepLines,
+        token: &CancellationToken,
+        on_file: &F,
+    ) -> SearchResult
+    where
+        F: Fn(Option<&FileResult>) + Sync,
+    {
+        if conditions.is_empty() {
+            return SearchResult::new(vec![], vec![]);
+        }
+
+        let find = conditions.iter().find(|c| c.is_matcher());
+        let line_filter = conditions.iter().find(|c| c.is_line_filter());
+
+        Self::collect(
+            grep_lines
+                .par_iter()
+                .filter_map(|(path, lines)| {
+                    if token.is_cancelled() {
+                        return None;
+                    }
+
+                    if !Self::is_selected(path, &config) {
+                        on_file(None);
+                        return None;
+                    }
+
+                    self.scan_file(path, &config, find, line_filter, Some(lines), on_file)
+                })
+                .collect(),
             conditions,
         )
     }
 
+    fn collect(scanned: Vec<Scanned>, conditions: Vec<SearchCondition>) -> SearchResult {
+        let (files, skipped) = scanned.into_iter().partition_result();
+        SearchResult::new(files, conditions).with_skipped(skipped)
+    }
+
+    /// Returns whether the path satisfies the file filters of the query.
+    fn is_selected(path: &str, config: &SearchConfig) -> bool {
+        config
+            .file_filters
+            .iter()
+            .filter_map(|c| c.file_filter())
+            .all(|f| f.filter(path))
+    }
+
+    /// Searches the file and reports it to `on_file`. Files that cannot be read are ignored,
+    /// while binary, undecodable and too large files are returned as skipped.
+    fn scan_file<F>(
+        &self,
+        path: &str,
+        config: &SearchConfig,
+        find: Option<&SearchCondition>,
+        line_filter: Option<&SearchCondition>,
+        only: Option<&BTreeSet<usize>>,
+        on_file: &F,
+    ) -> Option<Scanned>
+    where
+        F: Fn(Option<&FileResult>) + Sync,
+    {
+        match self.search_file(path, config, find, line_filter, only) {
+            Ok(file) => {
+                on_file(file.as_ref());
+                file.map(Ok)
+            }
+            Err(ReadError::Io(_)) => {
+                on_file(None);
+                None
+            }
+            Err(ReadError::Skip(skip)) => {
+                on_file(None);
+                Some(Err((path.to_string(), skip)))
+            }
+        }
+    }
+
+    /// Streams the file through the scanner, so that memory use does not depend on its size.
+    fn search_file(
+        &self,
+        path: &str,
+        config: &SearchConfig,
+        find: Option<&SearchCondition>,
+        line_filter: Option<&SearchCondition>,
+        only: Option<&BTreeSet<usize>>,
+    ) -> Result<Option<FileResult>, ReadError> {
+        let metadata = fs::metadata(path)?;
+
+        if config
+            .max_filesize
+            .is_some_and(|max| metadata.is_file() && metadata.len() > max)
+        {
+            return Err(ReadError::Skip(Skip::TooLarge));
+        }
+
+        let modified = metadata.modified().ok();
+        let mut hasher = StampHasher::default();
+        let mut lines = config.decoder.lines(path, config.mmap)?;
+        let mut scanner = Scanner::new(config, find, line_filter, only, lines.encoding());
+
+        while let Some(block) = lines.next_block() {
+            let block = block?;
+
+            hasher.update(block);
+            scanner.push_block(block);
+        }
+
+        let lines = scanner.finish();
+
+        if lines.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(FileResult {
+                file_path: path.to_string(),
+                lines,
+                stamp: Some(hasher.finish(modified)),
+            }))
+        }
+    }
+
     fn search_stdin(&self, config: SearchConfig, conditions: Vec<SearchCondition>) -> SearchResult {
         if conditions.is_empty() {
             return SearchResult::new(vec![], vec![]);
@@ -78,7 +251,8 @@ impl Search {
                 vec![FileResult {
                     // TODO: fix me
                     file_path: "".to_string(),
-                    lines: self.search_content(stdin, &config, find, line_filter),
+                    lines: self.search_content(&stdin, &config, find, line_filter),
+                    stamp: None,
                 }],
                 conditions,
             ),
@@ -87,6 +261,7 @@ impl Search {
                     // TODO: fix me
                     file_path: "".to_string(),
                     lines: vec![],
+                    stamp: None,
                 }],
                 conditions,
             ),
@@ -95,133 +270,43 @@ impl Search {
 
     fn search_content(
         &self,
-        content: String,
+        content: &str,
         config: &SearchConfig,
         find: Option<&SearchCondition>,
         line_filter: Option<&SearchCondition>,
     ) -> Vec<LineResult> {
-        let slice = content.lines().collect::<Vec<_>>();
-
-        content
-            .lines()
-            .enumerate()
-            .flat_map(|(index, line)| {
-                if let Some(f) = &line_filter {
-                    if let Some(line_filter) = f.line_filter() {
-                        if !line_filter.filter(line) {
-                            return vec![];
-                        }
-                    }
-                }
-
-                if let Some(f) = &find {
-                    f.matcher().and_then(|f| {
-                        let line = line.replace('\t', " ");
-                        let matches = MatchResult::find(line.to_string(), f, 1);
-
-                        if matches.is_empty() {
-                            None
-                        } else {
-                            let before = if let Some(before) = config.before_context {
-                                let start = if index < before {
-                                    0
-                                } else {
-                                    index.saturating_sub(before)
-                                };
-                                slice[start..index]
-                                    .iter()
-                                    .enumerate()
-                                    .map(|(i, it)| {
-                                        LineResult::Line(Line::new(
-                                            start + i,
-                                            it.to_string(),
-                                            vec![],
-                                            false,
-                                        ))
-                                    })
-                                    .collect_vec()
-                            } else {
-                                vec![]
-                            };
-
-                            let after = if let Some(after) = config.after_context {
-                                let end = if index + after >= slice.len() {
-                                    slice.len()
-                                } else {
-                                    index + after + 1
-                                };
-                                slice[index + 1..end]
-                                    .iter()
-                                    .enumerate()
-                                    .map(|(i, it)| {
-                                        LineResult::Line(Line::new(
-                                            index + i,
-                                            it.to_string(),
-                                            vec![],
-                                            false,
-                                        ))
-                                    })
-                                    .collect_vec()
-                            } else {
-                                vec![]
-                            };
-
-                            Some(itertools::concat(vec![
-                                before.clone(),
-                                vec![LineResult::Line(Line::new(
-                                    index + 1,
-                                    line.to_string(),
-                                    matches,
-                                    false,
-                                ))],
-                                after.clone(),
-                                if before.len() + after.len() > 1 {
-                                    vec![LineResult::Separator]
-                                } else {
-                                    vec![]
-                                },
-                            ]))
-                        }
-                    })
-                } else {
-                    Some(vec![LineResult::Line(Line::new(
-                        index + 1,
-                        line.to_string(),
-                        Vec::new(),
-                        false,
-                    ))])
-                }
-                .unwrap_or_default()
-            })
-            .collect::<Vec<_>>()
+        let mut scanner = Scanner::new(config, find, line_filter, None, UTF_8);
+
+        scanner.push_block(content);
+        scanner.finish()
     }
 
-    fn entries(&self, path: &str, config: &SearchConfig) -> Vec<String> {
+    fn entries(
+        &self,
+        path: &str,
+        config: &SearchConfig,
+        filter: &WalkFilter,
+        token: &CancellationToken,
+    ) -> Vec<String> {
         if Path::new(path).is_file() {
             vec![path.to_string()]
         } else {
             let mut walk_builder = WalkBuilder::new(path);
-            let mut overrides_builder = OverrideBuilder::new(".");
-
-            if let Some(exclude) = &config.exclude_path {
-                overrides_builder
-                    .add(format!("!{}", exclude).as_str())
-                    .unwrap();
-                walk_builder.overrides(overrides_builder.build().unwrap());
-            }
 
+            filter.apply(&mut walk_builder);
             walk_builder
                 .git_ignore(!config.no_git_ignore)
                 .git_exclude(!config.no_git_exclude)
                 .hidden(config.hidden)
                 .max_depth(config.max_depth)
                 .build()
+                .take_while(|_| !token.is_cancelled())
                 .filter_map(|entry| {
                     entry
                         .map_err(|err| eprintln!("{}", err.to_string().bold().red()))
                         .ok()
                 })
-                .map(|entry| entry.path().to_str().unwrap().to_string())
+                .filter_map(|entry| entry.path().to_str().map(|path| path.to_string()))
                 .collect_vec()
         }
     }
@@ -0,0 +1,335 @@
+use crate::filter::Filter;
+use crate::matcher::{Matcher, Prefilter};
+use crate::models::decoder::encoded_len;
+use crate::models::file_result::LineResult;
+use crate::models::line_reader::{terminator, trim_terminator};
+use crate::models::text_file::BOM;
+use crate::models::{Line, MatchResult, Position, SearchCondition, SearchConfig};
+use encoding_rs::Encoding;
+use memchr::{memchr, memchr_iter, memrchr_iter};
+use std::collections::{BTreeSet, VecDeque};
+use std::sync::Arc;
+
+/// Collects the lines of a file that match, with their context, as the file is fed block by block.
+/// Besides the results only the last `before_context` lines are held, so files of any size can be scanned.
+/// Lines without the literals that every match contains are skipped without running the matcher.
+/// Each collected line records its byte offset in the file, counted in the file's encoding.
+pub struct Scanner<'a> {
+    matcher: Option<Arc<dyn Matcher>>,
+    prefilter: Option<Prefilter>,
+    line_filter: Option<Arc<dyn Filter>>,
+    only: Option<&'a BTreeSet<usize>>,
+    before_context: usize,
+    after_context: usize,
+    encoding: &'static Encoding,
+    /// The number of the next line, starting at 1.
+    line_no: usize,
+    /// The byte offset of the next line in the file.
+    offset: usize,
+    /// The lines before the next match that have not been collected yet.
+    before: VecDeque<Line>,
+    /// The number of lines still to collect after the last match.
+    after: usize,
+    last_line_no: Option<usize>,
+    lines: Vec<LineResult>,
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(
+        config: &SearchConfig,
+        find: Option<&SearchCondition>,
+        line_filter: Option<&SearchCondition>,
+        only: Option<&'a BTreeSet<usize>>,
+        encoding: &'static Encoding,
+    ) -> Self {
+        let matcher = find.and_then(|f| f.matcher());
+        let context = |n: Option<usize>| if matcher.is_some() { n.unwrap_or(0) } else { 0 };
+
+        Self {
+            before_context: context(config.before_context),
+            after_context: context(config.after_context),
+            prefilter: matcher.as_deref().and_then(Prefilter::new),
+            matcher,
+            line_filter: line_filter.and_then(|f| f.line_filter()),
+            only,
+            encoding,
+            line_no: 1,
+            offset: 0,
+            before: VecDeque::new(),
+            after: 0,
+            last_line_no: None,
+            lines: Vec::new(),
+        }
+    }
+
+    /// Feeds the next whole lines with their terminators.
+    pub fn push_block(&mut self, block: &str) {
+        let mut pos = 0;
+
+        while pos < block.len() {
+            let candidate = match &self.prefilter {
+                Some(prefilter) => prefilter
+                    .find(block, pos)
+                    .map(|i| line_start(block, pos, i))
+                    .unwrap_or(block.len()),
+                None => pos,
+            };
+
+            self.skip(&block[pos..candidate]);
+
+            if candidate < block.len() {
+                let end = line_end(block, candidate);
+
+                self.push_line(&block[candidate..end]);
+                pos = end;
+            } else {
+                pos = candidate;
+            }
+        }
+    }
+
+    pub fn finish(self) -> Vec<LineResult> {
+        self.lines
+    }
+
+    fn push_line(&mut self, line: &str) {
+        let line_no = self.line_no;
+        let content = self.content(line);
+
+        if let Some(found) = self.find(line_no, content) {
+            while let Some(line) = self.before.pop_front() {
+                self.collect(line);
+            }
+
+            self.collect(found.with_position(self.position(line)));
+            self.after = self.after_context;
+            self.line_no += 1;
+            self.offset += encoded_len(line, self.encoding);
+        } else {
+            self.push_context(line);
+        }
+    }
+
+    /// Feeds a line that does not match.
+    fn push_context(&mut self, line: &str) {
+        if self.after > 0 || self.before_context > 0 {
+            let context = Line::new(
+                self.line_no,
+                self.content(line).to_string(),
+                Vec::new(),
+                false,
+            )
+            .as_context()
+            .with_position(self.position(line));
+
+            if self.after > 0 {
+                self.after -= 1;
+                self.collect(context);
+            } else {
+                if self.before.len() == self.before_context {
+                    self.before.pop_front();
+                }
+
+                self.before.push_back(context);
+            }
+        }
+
+        self.line_no += 1;
+        self.offset += encoded_len(line, self.encoding);
+    }
+
+    /// Feeds lines that cannot match, only looking at the ones that can be context.
+    fn skip(&mut self, mut lines: &str) {
+        while self.after > 0 && !lines.is_empty() {
+            let end = line_end(lines, 0);
+
+            self.push_context(&lines[..end]);
+            lines = &lines[end..];
+        }
+
+        if lines.is_empty() {
+            return;
+        }
+
+        let bytes = lines.as_bytes();
+        let count = memchr_iter(b'\n', bytes).count() + usize::from(!lines.ends_with('\n'));
+        let keep = count.min(self.before_context);
+        let start = match keep {
+            0 => lines.len(),
+            _ => memrchr_iter(b'\n', &bytes[..bytes.len() - 1])
+                .nth(keep - 1)
+                .map_or(0, |i| i + 1),
+        };
+
+        self.line_no += count - keep;
+        self.offset += encoded_len(&lines[..start], self.encoding);
+        lines[start..]
+            .split_inclusive('\n')
+            .for_each(|line| self.push_context(line));
+    }
+
+    fn position(&self, line: &str) -> Position {
+        Position {
+            offset: self.offset,
+            terminator: terminator(line),
+        }
+    }
+
+    /// Returns the line without its terminator, and without the BOM on the first line.
+    fn content<'b>(&self, line: &'b str) -> &'b str {
+        let content = trim_terminator(line);
+
+        match self.line_no {
+            1 => content.strip_prefix(BOM).unwrap_or(content),
+            _ => content,
+        }
+    }
+
+    fn find(&self, line_no: usize, line: &str) -> Option<Line> {
+        if self.only.is_some_and(|only| !only.contains(&line_no))
+            || self.line_filter.as_ref().is_some_and(|f| !f.filter(line))
+        {
+            return None;
+        }
+
+        match &self.matcher {
+            Some(matcher) => {
+                let text = line.replace('\t', " ");
+
+                matcher.is_match(&text).then(|| {
+                    let matches = MatchResult::find(text, Arc::clone(matcher), 1);
+                    Line::new(line_no, line.to_string(), matches, false)
+                })
+            }
+            None => Some(Line::new(line_no, line.to_string(), Vec::new(), false)),
+        }
+    }
+
+    /// Adds the line to the results, separated from the previous group if there is a gap.
+    fn collect(&mut self, line: Line) {
+        if self.before_context + self.after_context > 0
+            && self.last_line_no.is_some_and(|n| n + 1 < line.line_no)
+        {
+            self.lines.push(LineResult::Separator);
+        }
+
+        self.last_line_no = Some(line.line_no);
+        self.lines.push(LineResult::Line(Arc::new(line)));
+    }
+}
+
+/// The start of the line that contains `i`, which is not before `start`.
+fn line_start(text: &str, start: usize, i: usize) -> usize {
+    memrchr_iter(b'\n', &text.as_bytes()[start..i])
+        .next()
+        .map_or(start, |n| start + n + 1)
+}
+
+/// The end of the line that starts at `start`, after its terminator.
+fn line_end(text: &str, start: usize) -> usize {
+    memchr(b'\n', &text.as_bytes()[start..]).map_or(text.len(), |n| start + n + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encoding_rs::{UTF_16LE, UTF_8, WINDOWS_1252};
+    use rstest::rstest;
+
+    fn scan(
+        before_context: Option<usize>,
+        after_context: Option<usize>,
+        find: SearchCondition,
+        blocks: &[&str],
+    ) -> Vec<Option<(usize, String)>> {
+        let config = SearchConfig {
+            before_context,
+            after_context,
+            ..SearchConfig::default()
+        };
+        let mut scanner = Scanner::new(&config, Some(&find), None, None, UTF_8);
+
+        blocks.iter().for_each(|block| scanner.push_block(block));
+        scanner
+            .finish()
+            .iter()
+            .map(|line| line.line().map(|l| (l.line_no, l.text.clone())))
+            .collect()
+    }
+
+    #[rstest]
+    #[case(None, None, "foo\nbar\nfoo", vec![Some(1), Some(3)])]
+    #[case(Some(1), None, "a\nb\nfoo\nc", vec![Some(2), Some(3)])]
+    #[case(None, Some(1), "foo\na\nb\nfoo\nc", vec![Some(1), Some(2), None, Some(4), Some(5)])]
+    #[case(Some(1), Some(1), "a\nfoo\nb\nfoo\nc", vec![Some(1), Some(2), Some(3), Some(4), Some(5)])]
+    #[case(Some(2), Some(2), "foo\nfoo\na\nb\nc\nd\ne\nfoo", vec![Some(1), Some(2), Some(3), Some(4), None, Some(6), Some(7), Some(8)])]
+    #[case(Some(3), None, "a\nfoo", vec![Some(1), Some(2)])]
+    fn test_context(
+        #[case] before_context: Option<usize>,
+        #[case] after_context: Option<usize>,
+        #[case] text: &str,
+        #[case] expected: Vec<Option<usize>>,
+    ) {
+        let exact = SearchCondition::Exact("foo".to_string());
+        let regex = SearchCondition::Regex("f(o)o".to_string());
+
+        for find in [exact, regex] {
+            assert_eq!(
+                scan(before_context, after_context, find, &[text])
+                    .into_iter()
+                    .map(|line| line.map(|(line_no, _)| line_no))
+                    .collect::<Vec<_>>(),
+                expected
+            );
+        }
+    }
+
+    #[rstest]
+    #[case(SearchCondition::Exact("foo".to_string()), &["a\nfoo\n", "b\r\nc foo\r\n"], vec![(1, "a"), (2, "foo"), (3, "b"), (4, "c foo")])]
+    #[case(SearchCondition::Exact("foo bar".to_string()), &["\u{feff}foo\tbar\n"], vec![(1, "foo\tbar")])]
+    #[case(SearchCondition::IgnoreCase("FOO".to_string()), &["x\n", "Foo\n"], vec![(1, "x"), (2, "Foo")])]
+    #[case(SearchCondition::Exact("foo".to_string()), &["\u{feff}a\n", "foo"], vec![(1, "a"), (2, "foo")])]
+    fn test_push_block(
+        #[case] find: SearchCondition,
+        #[case] blocks: &[&str],
+        #[case] expected: Vec<(usize, &str)>,
+    ) {
+        assert_eq!(
+            scan(Some(1), None, find, blocks),
+            expected
+                .into_iter()
+                .map(|(line_no, text)| Some((line_no, text.to_string())))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[rstest]
+    #[case(UTF_8, None, &["x\ny\r\n", "foo\r\nz"], vec![(5, "\r\n")])]
+    #[case(UTF_8, Some(1), &["é\nfoo"], vec![(0, "\n"), (3, "")])]
+    #[case(WINDOWS_1252, Some(1), &["café\n", "foo\n"], vec![(0, "\n"), (5, "\n")])]
+    #[case(UTF_16LE, None, &["\u{feff}a\n", "foo"], vec![(6, "")])]
+    fn test_positions(
+        #[case] encoding: &'static Encoding,
+        #[case] before_context: Option<usize>,
+        #[case] blocks: &[&str],
+        #[case] expected: Vec<(usize, &str)>,
+    ) {
+        let config = SearchConfig {
+            before_context,
+            ..SearchConfig::default()
+        };
+        let find = SearchCondition::Exact("foo".to_string());
+        let mut scanner = Scanner::new(&config, Some(&find), None, None, encoding);
+
+        blocks.iter().for_each(|block| scanner.push_block(block));
+        assert_eq!(
+            scanner
+                .finish()
+                .iter()
+                .filter_map(|line| line.line()?.position)
+                .map(|p| (p.offset, p.terminator))
+                .collect::<Vec<_>>(),
+            expected
+        );
+    }
+}
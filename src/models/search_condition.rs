@@ -1,7 +1,8 @@
 use crate::{
     filter::{self},
     matcher::{self},
-    parser, transform,
+    parser::{self, quote, quote_exact},
+    transform,
 };
 use anyhow::{anyhow, Result};
 use std::{
@@ -32,7 +33,10 @@ pub const LINE_INVERT_MATCH_REGEX: &str = "line.invert_match_regex";
 pub const LINE_LENGTH: &str = "line.length";
 pub const LINE_BYTE_LENGTH: &str = "line.bytelength";
 pub const LINE_CONTAINS: &str = "line.contains";
+pub const FILE_EXT: &str = "file.ext";
+pub const FILE_PATH: &str = "file.path";
 pub const REPLACE: &str = "replace";
+pub const REGEX_REPLACE: &str = "regex_replace";
 pub const INSERT: &str = "insert";
 pub const DELETE: &str = "delete";
 pub const CAMEL_CASE: &str = "camel_case";
@@ -47,6 +51,9 @@ pub const LOWER_CASE: &str = "lower_case";
 pub const UPPER_CAMEL_CASE: &str = "upper_camel_case";
 pub const UPPER_KEBAB_CASE: &str = "upper_kebab_case";
 pub const UPPER_SNAKE_CASE: &str = "upper_snake_case";
+pub const AND: &str = "&&";
+pub const OR: &str = "||";
+pub const NOT: &str = "!";
 
 #[derive(Clone, Debug, PartialEq, EnumIter)]
 #[strum(serialize_all = "snake_case")]
@@ -72,8 +79,12 @@ pub enum SearchCondition {
     LineInvertMatchRegex(String),
     LineLength(Option<Op>),
     LineByteLength(Option<Op>),
+    // file filter
+    FileExt(String),
+    FilePath(String),
     // transform
     Replace(String, String),
+    RegexReplace(String, String),
     Insert(usize, String),
     Delete(usize, usize),
     Update(String),
@@ -89,23 +100,27 @@ pub enum SearchCondition {
     UpperCamelCase,
     UpperKebabCase,
     UpperSnakeCase,
+    // boolean expression
+    #[strum(disabled)]
+    And(Box<SearchCondition>, Box<SearchCondition>),
+    #[strum(disabled)]
+    Or(Box<SearchCondition>, Box<SearchCondition>),
+    #[strum(disabled)]
+    Not(Box<SearchCondition>),
 }
 
 impl FromStr for SearchCondition {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match parser::any_condition(s) {
-            Ok((_, c)) => c,
-            Err(e) => Err(anyhow!(e.to_string())),
-        }
+        parser::parse_condition(s)
     }
 }
 
 impl Display for SearchCondition {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         let s = match self {
-            SearchCondition::Exact(s) => s.to_string(),
+            SearchCondition::Exact(s) => quote_exact(s),
             SearchCondition::Number(Some(Op::Eq(v))) => format!("{}() == {}", NUMBER, v),
             SearchCondition::Number(Some(Op::Ne(v))) => format!("{}() != {}", NUMBER, v),
             SearchCondition::Number(Some(Op::Gt(v))) => format!("{}() > {}", NUMBER, v),
@@ -113,21 +128,21 @@ impl Display for SearchCondition {
             SearchCondition::Number(Some(Op::Lt(v))) => format!("{}() < {}", NUMBER, v),
             SearchCondition::Number(Some(Op::Lte(v))) => format!("{}() <= {}", NUMBER, v),
             SearchCondition::Number(_) => format!("{}()", NUMBER),
-            SearchCondition::IgnoreCase(s) => format!("{}({})", IGNORE_CASE, &s),
-            SearchCondition::WholeWord(s) => format!("{}({})", WHOLE_WORD, &s),
-            SearchCondition::Regex(s) => format!("{}({})", REGEX, &s),
-            SearchCondition::Contains(s) => format!("{}({})", CONTAINS, &s),
-            SearchCondition::StartsWith(s) => format!("{}({})", STARTS_WITH, &s),
-            SearchCondition::EndsWith(s) => format!("{}({})", ENDS_WITH, &s),
-            SearchCondition::InvertMatch(s) => format!("{}({})", INVERT_MATCH, &s),
-            SearchCondition::InvertMatchRegex(s) => format!("{}({})", INVERT_MATCH_REGEX, &s),
-            SearchCondition::LineContains(s) => format!("{}({})", LINE_CONTAINS, &s),
-            SearchCondition::LineRegex(s) => format!("{}({})", LINE_REGEX, &s),
-            SearchCondition::LineStartsWith(s) => format!("{}({})", LINE_STARTS_WITH, &s),
-            SearchCondition::LineEndsWith(s) => format!("{}({})", LINE_ENDS_WITH, &s),
-            SearchCondition::LineInvertMatch(s) => format!("{}({})", LINE_INVERT_MATCH, &s),
+            SearchCondition::IgnoreCase(s) => format!("{}({})", IGNORE_CASE, quote(s)),
+            SearchCondition::WholeWord(s) => format!("{}({})", WHOLE_WORD, quote(s)),
+            SearchCondition::Regex(s) => format!("{}({})", REGEX, quote(s)),
+            SearchCondition::Contains(s) => format!("{}({})", CONTAINS, quote(s)),
+            SearchCondition::StartsWith(s) => format!("{}({})", STARTS_WITH, quote(s)),
+            SearchCondition::EndsWith(s) => format!("{}({})", ENDS_WITH, quote(s)),
+            SearchCondition::InvertMatch(s) => format!("{}({})", INVERT_MATCH, quote(s)),
+            SearchCondition::InvertMatchRegex(s) => format!("{}({})", INVERT_MATCH_REGEX, quote(s)),
+            SearchCondition::LineContains(s) => format!("{}({})", LINE_CONTAINS, quote(s)),
+            SearchCondition::LineRegex(s) => format!("{}({})", LINE_REGEX, quote(s)),
+            SearchCondition::LineStartsWith(s) => format!("{}({})", LINE_STARTS_WITH, quote(s)),
+            SearchCondition::LineEndsWith(s) => format!("{}({})", LINE_ENDS_WITH, quote(s)),
+            SearchCondition::LineInvertMatch(s) => format!("{}({})", LINE_INVERT_MATCH, quote(s)),
             SearchCondition::LineInvertMatchRegex(s) => {
-                format!("{}({})", LINE_INVERT_MATCH_REGEX, &s)
+                format!("{}({})", LINE_INVERT_MATCH_REGEX, quote(s))
             }
 
             SearchCondition::LineLength(Some(Op::Eq(v))) => {
@@ -168,10 +183,16 @@ impl Display for SearchCondition {
                 format!("{}()", LINE_BYTE_LENGTH)
             }
 
-            SearchCondition::Replace(f, t) => format!("{}({}, {})", REPLACE, &f, &t),
-            SearchCondition::Insert(i, v) => format!("{}({}, {})", INSERT, &i, &v),
+            SearchCondition::FileExt(s) => format!("{}({})", FILE_EXT, quote(s)),
+            SearchCondition::FilePath(s) => format!("{}({})", FILE_PATH, quote(s)),
+
+            SearchCondition::Replace(f, t) => format!("{}({}, {})", REPLACE, quote(f), quote(t)),
+            SearchCondition::RegexReplace(r, t) => {
+                format!("{}({}, {})", REGEX_REPLACE, quote(r), quote(t))
+            }
+            SearchCondition::Insert(i, v) => format!("{}({}, {})", INSERT, &i, quote(v)),
             SearchCondition::Delete(s, e) => format!("{}({}, {})", DELETE, &s, &e),
-            SearchCondition::Update(v) => format!("{}({})", UPDATE, &v),
+            SearchCondition::Update(v) => format!("{}({})", UPDATE, quote(v)),
             SearchCondition::CamelCase => format!("{}()", CAMEL_CASE),
             SearchCondition::KebabCase => format!("{}()", KEBAB_CASE),
             SearchCondition::SnakeCase => format!("{}()", SNAKE_CASE),
@@ -184,6 +205,11 @@ impl Display for SearchCondition {
             SearchCondition::UpperCamelCase => format!("{}()", UPPER_CAMEL_CASE),
             SearchCondition::UpperKebabCase => format!("{}()", UPPER_KEBAB_CASE),
             SearchCondition::UpperSnakeCase => format!("{}()", UPPER_SNAKE_CASE),
+            SearchCondition::And(l, r) => {
+                format!("{} {} {}", l.operand(self), AND, r.operand(self))
+            }
+            SearchCondition::Or(l, r) => format!("{} {} {}", l.operand(self), OR, r.operand(self)),
+            SearchCondition::Not(c) => format!("{}{}", NOT, c.operand(self)),
         };
 
         write!(f, "{}", s)
@@ -209,12 +235,76 @@ impl SearchCondition {
             .map(|_| SearchCondition::LineInvertMatchRegex(s.to_string()))
     }
 
+    pub fn new_file_path(s: &str) -> Result<SearchCondition> {
+        filter::FilePath::new(s.to_string()).map(|_| SearchCondition::FilePath(s.to_string()))
+    }
+
+    pub fn new_regex_replace(regex: &str, template: &str) -> Result<SearchCondition> {
+        transform::RegexReplace::new(regex.to_string(), template.to_string())
+            .map(|_| SearchCondition::RegexReplace(regex.to_string(), template.to_string()))
+    }
+
+    pub fn new_and(lhs: SearchCondition, rhs: SearchCondition) -> Result<SearchCondition> {
+        Self::verify_operand(&lhs)?;
+        Self::verify_operand(&rhs)?;
+        Ok(SearchCondition::And(Box::new(lhs), Box::new(rhs)))
+    }
+
+    pub fn new_or(lhs: SearchCondition, rhs: SearchCondition) -> Result<SearchCondition> {
+        Self::verify_operand(&lhs)?;
+        Self::verify_operand(&rhs)?;
+        Ok(SearchCondition::Or(Box::new(lhs), Box::new(rhs)))
+    }
+
+    pub fn new_not(c: SearchCondition) -> Result<SearchCondition> {
+        Self::verify_operand(&c)?;
+        Ok(SearchCondition::Not(Box::new(c)))
+    }
+
+    fn verify_operand(c: &SearchCondition) -> Result<()> {
+        if c.is_matcher() || c.is_line_filter() {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "Only matchers and line filters can be combined with {}, {} or {}: {}",
+                AND,
+                OR,
+                NOT,
+                c
+            ))
+        }
+    }
+
+    fn precedence(&self) -> usize {
+        match self {
+            SearchCondition::Or(_, _) => 1,
+            SearchCondition::And(_, _) => 2,
+            _ => 3,
+        }
+    }
+
+    fn operand(&self, parent: &SearchCondition) -> String {
+        if self.precedence() < parent.precedence() {
+            format!("({})", self)
+        } else {
+            self.to_string()
+        }
+    }
+
     pub fn is_matcher(&self) -> bool {
-        matches!(self, SearchCondition::Exact(_))
-            || matches!(self, SearchCondition::Number(_))
-            || matches!(self, SearchCondition::IgnoreCase(_))
-            || matches!(self, SearchCondition::WholeWord(_))
-            || matches!(self, SearchCondition::Regex(_))
+        match self {
+            SearchCondition::And(l, r) | SearchCondition::Or(l, r) => {
+                l.is_matcher() || r.is_matcher()
+            }
+            SearchCondition::Not(c) => c.is_matcher(),
+            _ => {
+                matches!(self, SearchCondition::Exact(_))
+                    || matches!(self, SearchCondition::Number(_))
+                    || matches!(self, SearchCondition::IgnoreCase(_))
+                    || matches!(self, SearchCondition::WholeWord(_))
+                    || matches!(self, SearchCondition::Regex(_))
+            }
+        }
     }
 
     pub fn is_filter(&self) -> bool {
@@ -226,18 +316,31 @@ impl SearchCondition {
     }
 
     pub fn is_line_filter(&self) -> bool {
-        matches!(self, SearchCondition::LineRegex(_))
-            || matches!(self, SearchCondition::LineStartsWith(_))
-            || matches!(self, SearchCondition::LineEndsWith(_))
-            || matches!(self, SearchCondition::LineInvertMatch(_))
-            || matches!(self, SearchCondition::LineInvertMatchRegex(_))
-            || matches!(self, SearchCondition::LineLength(_))
-            || matches!(self, SearchCondition::LineByteLength(_))
-            || matches!(self, SearchCondition::LineContains(_))
+        match self {
+            SearchCondition::And(l, r) | SearchCondition::Or(l, r) => {
+                l.is_line_filter() && r.is_line_filter()
+            }
+            SearchCondition::Not(c) => c.is_line_filter(),
+            _ => {
+                matches!(self, SearchCondition::LineRegex(_))
+                    || matches!(self, SearchCondition::LineStartsWith(_))
+                    || matches!(self, SearchCondition::LineEndsWith(_))
+                    || matches!(self, SearchCondition::LineInvertMatch(_))
+                    || matches!(self, SearchCondition::LineInvertMatchRegex(_))
+                    || matches!(self, SearchCondition::LineLength(_))
+                    || matches!(self, SearchCondition::LineByteLength(_))
+                    || matches!(self, SearchCondition::LineContains(_))
+            }
+        }
+    }
+
+    pub fn is_file_filter(&self) -> bool {
+        matches!(self, SearchCondition::FileExt(_)) || matches!(self, SearchCondition::FilePath(_))
     }
 
     pub fn is_transform(&self) -> bool {
         matches!(self, SearchCondition::Replace(_, _))
+            || matches!(self, SearchCondition::RegexReplace(_, _))
             || matches!(self, SearchCondition::Insert(_, _))
             || matches!(self, SearchCondition::Delete(_, _))
             || matches!(self, SearchCondition::Update(_))
@@ -259,8 +362,10 @@ impl SearchCondition {
         self.is_matcher()
             || self.is_filter()
             || self.is_line_filter()
+            || self.is_file_filter()
             || !matches!(self, SearchCondition::Exact(_))
             || matches!(self, SearchCondition::Replace(_, _))
+            || matches!(self, SearchCondition::RegexReplace(_, _))
             || matches!(self, SearchCondition::Insert(_, _))
             || matches!(self, SearchCondition::Delete(_, _))
             || matches!(self, SearchCondition::Update(_))
@@ -273,10 +378,27 @@ impl SearchCondition {
             SearchCondition::IgnoreCase(s) => Some(matcher::IgnoreCase::new(s.to_string())),
             SearchCondition::WholeWord(s) => Some(matcher::WholeWord::new(s.to_string())),
             SearchCondition::Regex(s) => Some(matcher::Regex::new(s.to_string()).unwrap()),
+            SearchCondition::And(l, r) if self.is_matcher() => Some(matcher::And::new(
+                l.operand_matcher()?,
+                r.operand_matcher()?,
+            )),
+            SearchCondition::Or(l, r) if self.is_matcher() => {
+                Some(matcher::Or::new(l.operand_matcher()?, r.operand_matcher()?))
+            }
+            SearchCondition::Not(c) if self.is_matcher() => {
+                Some(matcher::Not::new(c.operand_matcher()?))
+            }
             _ => None,
         }
     }
 
+    fn operand_matcher(&self) -> Option<Arc<dyn matcher::Matcher>> {
+        self.matcher().or_else(|| {
+            self.line_filter()
+                .map(|f| matcher::LineFilter::new(f) as Arc<dyn matcher::Matcher>)
+        })
+    }
+
     pub fn match_filter(&self) -> Option<Arc<dyn filter::Filter>> {
         match self {
             SearchCondition::Contains(s) => Some(filter::Contains::new(s.to_string())),
@@ -302,6 +424,24 @@ impl SearchCondition {
             }
             SearchCondition::LineLength(Some(op)) => Some(filter::Length::new(false, op)),
             SearchCondition::LineByteLength(Some(op)) => Some(filter::Length::new(true, op)),
+            SearchCondition::And(l, r) if self.is_line_filter() => {
+                Some(filter::And::new(l.line_filter()?, r.line_filter()?))
+            }
+            SearchCondition::Or(l, r) if self.is_line_filter() => {
+                Some(filter::Or::new(l.line_filter()?, r.line_filter()?))
+            }
+            SearchCondition::Not(c) if self.is_line_filter() => {
+                Some(filter::Not::new(c.line_filter()?))
+            }
+            _ => None,
+        }
+    }
+
+    /// A filter on the file path, applied to every line of the file.
+    pub fn file_filter(&self) -> Option<Arc<dyn filter::Filter>> {
+        match self {
+            SearchCondition::FileExt(s) => Some(filter::FileExt::new(s.to_string())),
+            SearchCondition::FilePath(s) => Some(filter::FilePath::new(s.to_string()).ok()?),
             _ => None,
         }
     }
@@ -311,6 +451,9 @@ impl SearchCondition {
             SearchCondition::Replace(f, t) => {
                 Some(transform::Replace::new(f.to_string(), t.to_string()))
             }
+            SearchCondition::RegexReplace(r, t) => {
+                Some(transform::RegexReplace::new(r.to_string(), t.to_string()).unwrap())
+            }
             SearchCondition::Insert(i, v) => Some(transform::Insert::new(*i, v.to_string())),
             SearchCondition::Delete(s, e) => {
                 Some(transform::Delete::new(Range { start: *s, end: *e }))
@@ -374,8 +517,12 @@ impl SearchCondition {
             SearchCondition::LineByteLength(Some(Op::Lte(Value::Num(n)))) => Some(n.to_string()),
             SearchCondition::LineByteLength(_) => None,
 
-            SearchCondition::Replace(f, t) => Some(format!("{}, {}", f, t)),
-            SearchCondition::Insert(i, v) => Some(format!("{}, {}", i, v)),
+            SearchCondition::FileExt(s) => Some(s.to_string()),
+            SearchCondition::FilePath(s) => Some(s.to_string()),
+
+            SearchCondition::Replace(f, t) => Some(format!("{}, {}", quote(f), quote(t))),
+            SearchCondition::RegexReplace(r, t) => Some(format!("{}, {}", quote(r), quote(t))),
+            SearchCondition::Insert(i, v) => Some(format!("{}, {}", i, quote(v))),
             SearchCondition::Delete(s, e) => Some(format!("{}, {}", s, e)),
             SearchCondition::Update(v) => Some(v.to_owned()),
             SearchCondition::Constant => None,
@@ -390,6 +537,9 @@ impl SearchCondition {
             SearchCondition::UpperCamelCase => None,
             SearchCondition::UpperKebabCase => None,
             SearchCondition::UpperSnakeCase => None,
+            SearchCondition::And(_, _) | SearchCondition::Or(_, _) | SearchCondition::Not(_) => {
+                Some(self.to_string())
+            }
         }
     }
 
@@ -418,11 +568,17 @@ impl SearchCondition {
             SearchCondition::LineInvertMatchRegex(_) => {
                 Ok(SearchCondition::LineInvertMatchRegex(s.to_string()))
             }
-            SearchCondition::Replace(_, _) => match s.split(',').collect::<Vec<_>>().as_slice() {
+            SearchCondition::FileExt(_) => Ok(SearchCondition::FileExt(s.to_string())),
+            SearchCondition::FilePath(_) => Ok(SearchCondition::FilePath(s.to_string())),
+            SearchCondition::Replace(_, _) => match parser::arguments(&s).as_slice() {
                 [f, t] => Ok(SearchCondition::Replace(f.to_string(), t.to_string())),
                 _ => Ok(SearchCondition::Replace(s.to_string(), "".to_string())),
             },
-            SearchCondition::Insert(_, _) => match s.split(',').collect::<Vec<_>>().as_slice() {
+            SearchCondition::RegexReplace(_, _) => match parser::arguments(&s).as_slice() {
+                [r, t] => Ok(SearchCondition::RegexReplace(r.to_string(), t.to_string())),
+                _ => Ok(SearchCondition::RegexReplace(s.to_string(), "".to_string())),
+            },
+            SearchCondition::Insert(_, _) => match parser::arguments(&s).as_slice() {
                 [i, v] => Ok(SearchCondition::Insert(
                     i.parse::<usize>().unwrap_or_default(),
                     v.to_string(),
@@ -453,5 +609,18 @@ mod tests {
             SearchCondition::IgnoreCase("test".to_string()).to_string(),
             "ignore_case(test)"
         );
+        assert_eq!(
+            SearchCondition::And(
+                Box::new(SearchCondition::Or(
+                    Box::new(SearchCondition::Exact("a".to_string())),
+                    Box::new(SearchCondition::Exact("b".to_string()))
+                )),
+                Box::new(SearchCondition::Not(Box::new(
+                    SearchCondition::LineContains("c".to_string())
+                )))
+            )
+            .to_string(),
+            "(a || b) && !line.contains(c)"
+        );
     }
 }
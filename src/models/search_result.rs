@@ -1,25 +1,60 @@
+use super::decoder::Skip;
 use super::file_result::LineResult;
-use super::{FileResult, Line, MatchResult, SearchCondition};
+use super::{
+    Decoder, FileResult, FileStamp, Line, MatchResult, SearchCondition, TextFile, UndoJournal,
+};
 use crate::ui;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use itertools::{concat, Itertools};
 use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::fmt::{Display, Formatter};
-use std::fs::File;
-use std::io::prelude::*;
-use std::sync::mpsc;
-use std::{fmt, fs};
+use std::sync::{mpsc, Arc};
 
+/// The files of a search and the conditions applied to them. Files are shared with `Arc`,
+/// so cloning the result or keeping the files of earlier stages does not copy their lines.
 #[derive(Clone)]
 pub struct SearchResult {
-    pub files: Vec<FileResult>,
+    pub files: Vec<Arc<FileResult>>,
     conditions: Vec<SearchCondition>,
+    skipped: Vec<(String, Skip)>,
+    /// The files before each condition added by `apply`, with the number of conditions they were computed with,
+    /// so that deleting the last condition restores them instead of applying every condition again.
+    snapshots: Vec<(usize, Vec<Arc<FileResult>>)>,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Stat {
     pub file_count: usize,
     pub match_count: usize,
+    pub binary_count: usize,
+    pub undecodable_count: usize,
+    pub too_large_count: usize,
+}
+
+impl Stat {
+    /// Describes the files that were not searched, e.g. `2 binary files, 1 undecodable file`.
+    pub fn skipped_summary(&self) -> Option<String> {
+        let skipped = [
+            (self.binary_count, "binary"),
+            (self.undecodable_count, "undecodable"),
+            (self.too_large_count, "too large"),
+        ]
+        .iter()
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, kind)| {
+            format!(
+                "{} {} file{}",
+                count,
+                kind,
+                if *count == 1 { "" } else { "s" }
+            )
+        })
+        .join(", ");
+
+        (!skipped.is_empty()).then_some(skipped)
+    }
 }
 
 impl Display for SearchResult {
@@ -34,18 +69,32 @@ impl Display for SearchResult {
 
 impl SearchResult {
     pub fn new(files: Vec<FileResult>, conditions: Vec<SearchCondition>) -> Self {
-        Self { files, conditions }
+        Self {
+            files: files.into_iter().map(Arc::new).collect(),
+            conditions,
+            skipped: Vec::new(),
+            snapshots: Vec::new(),
+        }
+    }
+
+    /// Records the files that were not searched because they are binary or could not be decoded.
+    pub fn with_skipped(self, skipped: Vec<(String, Skip)>) -> Self {
+        Self { skipped, ..self }
+    }
+
+    pub fn skipped(&self) -> &[(String, Skip)] {
+        &self.skipped
     }
 
     pub fn stat(&self) -> Stat {
+        let count = |f: fn(&Skip) -> bool| self.skipped.iter().filter(|(_, s)| f(s)).count();
+
         Stat {
             file_count: self.files.len(),
-            match_count: self.files.iter().fold(0, |acc, f| {
-                acc + f.lines.iter().fold(0, |acc, l| match l {
-                    LineResult::Line(l) => acc + l.matches().iter().count(),
-                    LineResult::Separator => acc,
-                })
-            }),
+            match_count: self.files.iter().map(|f| f.match_count()).sum(),
+            binary_count: count(|s| *s == Skip::Binary),
+            undecodable_count: count(|s| matches!(s, Skip::Undecodable(_))),
+            too_large_count: count(|s| *s == Skip::TooLarge),
         }
     }
 
@@ -58,44 +107,137 @@ impl SearchResult {
             })
     }
 
-    pub fn delete_last_condition(&mut self) -> SearchResult {
-        let _ = self.conditions.pop();
-        self.reapply()
+    /// Puts together results that applied the same conditions to one file each, in the order of the files
+    /// of `self`, the search they were computed from.
+    pub fn merge(self, mut parts: Vec<SearchResult>) -> SearchResult {
+        let order = self
+            .files
+            .iter()
+            .enumerate()
+            .map(|(i, file)| (file.file_path.as_str(), i))
+            .collect::<HashMap<_, _>>();
+
+        parts.sort_by_key(|part| {
+            part.files
+                .first()
+                .and_then(|file| order.get(file.file_path.as_str()).copied())
+        });
+
+        let Some(first) = parts.first() else {
+            return self;
+        };
+
+        SearchResult {
+            files: parts.iter().flat_map(|part| part.files.clone()).collect(),
+            conditions: first.conditions.clone(),
+            skipped: self.skipped.clone(),
+            snapshots: first
+                .snapshots
+                .iter()
+                .enumerate()
+                .map(|(i, (count, _))| {
+                    (
+                        *count,
+                        parts
+                            .iter()
+                            .flat_map(|part| part.snapshots[i].1.clone())
+                            .collect(),
+                    )
+                })
+                .collect(),
+        }
     }
 
-    fn clear(&self) -> SearchResult {
+    /// Keeps only the marked lines, given as file path and line number.
+    pub fn retain_marked(&self, marks: &HashSet<(String, usize)>) -> SearchResult {
         SearchResult {
             files: self
                 .files
                 .iter()
                 .map(|file| FileResult {
                     file_path: file.file_path.clone(),
+                    stamp: file.stamp,
                     lines: file
                         .lines
                         .iter()
-                        .map(|line| match line {
-                            LineResult::Line(line) => LineResult::Line(Line::new(
-                                line.line_no,
-                                line.text.clone(),
-                                Vec::new(),
-                                false,
-                            )),
-                            LineResult::Separator => LineResult::Separator,
+                        .filter(|line| match line {
+                            LineResult::Line(l) => {
+                                marks.contains(&(file.file_path.clone(), l.line_no))
+                            }
+                            LineResult::Separator => false,
                         })
+                        .cloned()
                         .collect(),
                 })
+                .filter(|file| !file.lines.is_empty())
+                .map(Arc::new)
                 .collect(),
             conditions: self.conditions.clone(),
+            skipped: self.skipped.clone(),
+            snapshots: Vec::new(),
         }
     }
 
-    pub fn reflect(&self, tx: mpsc::Sender<ui::Event>) -> Result<()> {
-        self.files
-            .par_iter()
-            .map(|file| self.reflect_file(file, tx.clone()))
-            .collect::<Result<Vec<_>>>()?;
+    /// Restores the files from before the last condition, or applies the remaining conditions again
+    /// if the last one was used by the search itself.
+    pub fn delete_last_condition(&mut self) -> SearchResult {
+        let _ = self.conditions.pop();
 
-        Ok(())
+        match self.snapshots.pop() {
+            Some((count, files)) if count == self.conditions.len() => SearchResult {
+                files,
+                conditions: self.conditions.clone(),
+                skipped: self.skipped.clone(),
+                snapshots: self.snapshots.clone(),
+            },
+            _ => self.reapply(),
+        }
+    }
+
+    fn clear(&self) -> SearchResult {
+        SearchResult {
+            files: self
+                .files
+                .iter()
+                .map(|file| {
+                    Arc::new(FileResult {
+                        file_path: file.file_path.clone(),
+                        stamp: file.stamp,
+                        lines: file
+                            .lines
+                            .iter()
+                            .map(|line| match line {
+                                LineResult::Line(line) => {
+                                    LineResult::Line(Arc::new(line.with_matches(Vec::new(), false)))
+                                }
+                                LineResult::Separator => LineResult::Separator,
+                            })
+                            .collect(),
+                    })
+                })
+                .collect(),
+            conditions: Vec::new(),
+            skipped: self.skipped.clone(),
+            snapshots: Vec::new(),
+        }
+    }
+
+    /// Writes the transforms back to the files and returns the paths of files skipped
+    /// because they were modified since the search.
+    pub fn reflect(
+        &self,
+        tx: mpsc::Sender<ui::Event>,
+        journal: &UndoJournal,
+        decoder: &Decoder,
+    ) -> Result<Vec<String>> {
+        Ok(self
+            .files
+            .par_iter()
+            .map(|file| self.reflect_file(file, tx.clone(), journal, decoder))
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect())
     }
 
     pub fn apply(&self, c: SearchCondition, index: usize) -> SearchResult {
@@ -103,78 +245,114 @@ impl SearchResult {
             files: self
                 .files
                 .par_iter()
-                .map(|file| FileResult {
-                    file_path: file.file_path.clone(),
-                    lines: file
+                .map(|file| {
+                    let excluded = c.file_filter().is_some_and(|f| !f.filter(&file.file_path));
+
+                    if c.is_file_filter() && !excluded {
+                        return Arc::clone(file);
+                    }
+
+                    let lines = file
                         .lines
                         .iter()
-                        .map(|line| {
-                            if let LineResult::Line(line) = line {
-                                let matches = concat(vec![
-                                    line.matches().clone(),
-                                    c.matcher()
-                                        .map(|f| {
-                                            MatchResult::find(
-                                                line.text.to_string(),
-                                                f.clone(),
-                                                index,
-                                            )
-                                            .into_iter()
-                                            .filter_map(|m| {
-                                                if line.matches().contains(&m) {
-                                                    None
-                                                } else {
-                                                    Some(m)
-                                                }
-                                            })
-                                            .collect_vec()
-                                        })
-                                        .unwrap_or_default(),
-                                ]);
-
-                                let line = if !line.is_filtered() {
-                                    line.filtered(c.is_matcher() && matches.is_empty())
-                                } else {
-                                    line.filtered(true)
-                                };
-
-                                let matches = c
-                                    .match_filter()
-                                    .map(|f| {
-                                        matches
-                                            .iter()
-                                            .filter_map(|m| m.filter(f.clone(), index))
-                                            .collect()
-                                    })
-                                    .unwrap_or(matches);
-                                let matches = c
-                                    .transform()
-                                    .map(|f| {
-                                        matches
-                                            .iter()
-                                            .flat_map(|m| m.transform(f.clone(), index))
-                                            .collect()
-                                    })
-                                    .unwrap_or(matches);
-                                let line_filter = c.line_filter();
-                                let line = Line::new(
-                                    line.line_no,
-                                    line.text.clone(),
-                                    matches,
-                                    line.is_filtered(),
-                                );
-
-                                LineResult::Line(
-                                    line_filter.map(|f| line.filter(f)).unwrap_or(line),
-                                )
-                            } else {
-                                LineResult::Separator
+                        .map(|line| match line {
+                            LineResult::Line(line) => {
+                                LineResult::Line(Self::apply_line(&c, index, excluded, line))
                             }
+                            LineResult::Separator => LineResult::Separator,
                         })
-                        .collect(),
+                        .collect_vec();
+                    let unchanged = lines.iter().zip(&file.lines).all(|(a, b)| match (a, b) {
+                        (LineResult::Line(a), LineResult::Line(b)) => Arc::ptr_eq(a, b),
+                        _ => true,
+                    });
+
+                    if unchanged {
+                        Arc::clone(file)
+                    } else {
+                        Arc::new(FileResult {
+                            file_path: file.file_path.clone(),
+                            stamp: file.stamp,
+                            lines,
+                        })
+                    }
                 })
                 .collect(),
             conditions: concat(vec![self.conditions.clone(), vec![c]]),
+            skipped: self.skipped.clone(),
+            snapshots: concat(vec![
+                self.snapshots.clone(),
+                vec![(self.conditions.len(), self.files.clone())],
+            ]),
+        }
+    }
+
+    /// Applies the condition to a line, returning the same line if the condition leaves it as it is.
+    fn apply_line(
+        c: &SearchCondition,
+        index: usize,
+        excluded: bool,
+        line: &Arc<Line>,
+    ) -> Arc<Line> {
+        let matcher = c.matcher();
+        // Matchers see tabs as spaces, as they do in the search.
+        let text = match matcher {
+            Some(_) => line.text.replace('\t', " "),
+            None => String::new(),
+        };
+        let found = matcher
+            .clone()
+            .map(|f| {
+                MatchResult::find(text.clone(), f, index)
+                    .into_iter()
+                    .filter(|m| !line.matches().contains(m))
+                    .collect_vec()
+            })
+            .unwrap_or_default();
+        let filtered = match c.line_filter() {
+            Some(f) => !f.filter(&line.text),
+            None => {
+                line.is_filtered()
+                    || excluded
+                    || (c.is_matcher()
+                        && line.matches().is_empty()
+                        && found.is_empty()
+                        && !matcher.is_some_and(|f| f.is_match(&text)))
+            }
+        };
+
+        if found.is_empty()
+            && filtered == line.is_filtered()
+            && c.match_filter().is_none()
+            && c.transform().is_none()
+        {
+            return Arc::clone(line);
+        }
+
+        let matches = concat(vec![line.matches().clone(), found]);
+        let matches = c
+            .match_filter()
+            .map(|f| {
+                matches
+                    .iter()
+                    .filter_map(|m| m.filter(f.clone(), index))
+                    .collect()
+            })
+            .unwrap_or(matches);
+        let matches = c
+            .transform()
+            .map(|f| {
+                matches
+                    .iter()
+                    .flat_map(|m| m.transform(f.clone(), index))
+                    .collect()
+            })
+            .unwrap_or(matches);
+
+        if matches == *line.matches() && filtered == line.is_filtered() {
+            Arc::clone(line)
+        } else {
+            Arc::new(line.with_matches(matches, filtered))
         }
     }
 
@@ -182,82 +360,161 @@ impl SearchResult {
         self.conditions.iter().map(|s| s.to_string()).join(" | ")
     }
 
-    pub fn reflect_on_selected_row(&mut self, file_result: &FileResult, line: &Line) -> Result<()> {
+    pub fn reflect_on_selected_row(
+        &mut self,
+        file_result: &FileResult,
+        line: &Line,
+        journal: &UndoJournal,
+        decoder: &Decoder,
+    ) -> Result<()> {
         if file_result.contains_transformed() {
-            let text = fs::read_to_string(&file_result.file_path)?;
-            let mut lines = text.lines().map(|s| s.to_string()).collect_vec();
+            let mut text = TextFile::read(&file_result.file_path, decoder)?;
 
-            for m in line.matches() {
-                lines[line.line_no - 1] = m.apply(lines[line.line_no - 1].to_string());
+            if file_result.is_modified(&text) {
+                return Err(anyhow!(
+                    "{} was modified since the search",
+                    file_result.file_path
+                ));
             }
 
-            let mut file = File::create(&file_result.file_path)?;
-            file.write_all(lines.join("\n").as_bytes())?;
+            if let Some(content) = text.line(line.line_no) {
+                let content = line.apply_transforms(content);
+                text.set_line(line.line_no, content);
+            }
 
-            self.files = self
-                .files
-                .clone()
-                .into_iter()
-                .map(|f| {
-                    if f.file_path == file_result.file_path {
-                        FileResult {
-                            file_path: f.file_path.clone(),
-                            lines: f
-                                .lines
-                                .into_iter()
-                                .filter(|l| {
-                                    if let LineResult::Line(l) = l {
-                                        l.line_no != line.line_no
-                                    } else {
-                                        true
-                                    }
-                                })
-                                .collect_vec(),
+            journal.record(&file_result.file_path)?;
+            text.write(&file_result.file_path)?;
+
+            let stamp = file_result.stamp.map(|_| {
+                FileStamp::new(
+                    &text.to_string(),
+                    FileStamp::modified(&file_result.file_path),
+                )
+            });
+
+            // The earlier stages are updated too, so that deleting a condition does not bring the line back.
+            let replaced = |files: &[Arc<FileResult>]| {
+                files
+                    .iter()
+                    .map(|f| {
+                        if f.file_path == file_result.file_path {
+                            Arc::new(FileResult {
+                                file_path: f.file_path.clone(),
+                                stamp,
+                                lines: f
+                                    .lines
+                                    .iter()
+                                    .filter(|l| {
+                                        if let LineResult::Line(l) = l {
+                                            l.line_no != line.line_no
+                                        } else {
+                                            true
+                                        }
+                                    })
+                                    .cloned()
+                                    .collect_vec(),
+                            })
+                        } else {
+                            Arc::clone(f)
                         }
-                    } else {
-                        f
-                    }
-                })
-                .collect_vec();
+                    })
+                    .collect_vec()
+            };
+
+            self.files = replaced(&self.files);
+            self.snapshots = self
+                .snapshots
+                .iter()
+                .map(|(count, files)| (*count, replaced(files)))
+                .collect();
         }
 
         Ok(())
     }
 
-    fn reflect_file(&self, file: &FileResult, tx: mpsc::Sender<ui::Event>) -> Result<()> {
-        let text = fs::read_to_string(&file.file_path)?;
-        let mut lines = text.lines().map(|s| s.to_string()).collect_vec();
+    /// Renders the pending transforms as a unified diff without touching the files.
+    pub fn diff(&self, decoder: &Decoder) -> Result<String> {
+        Ok(self
+            .files
+            .par_iter()
+            .filter(|file| file.contains_transformed())
+            .map(|file| {
+                let (original, text) = self.transformed_file(file, decoder)?;
+
+                Ok(if file.is_modified(&original) {
+                    String::new()
+                } else {
+                    original.diff(&text, &file.file_path)
+                })
+            })
+            .collect::<Result<Vec<_>>>()?
+            .concat())
+    }
+
+    fn reflect_file(
+        &self,
+        file: &FileResult,
+        tx: mpsc::Sender<ui::Event>,
+        journal: &UndoJournal,
+        decoder: &Decoder,
+    ) -> Result<Option<String>> {
+        let (original, text) = self.transformed_file(file, decoder)?;
+
+        if file.is_modified(&original) {
+            tx.send(ui::Event::Progress(1)).ok();
+            return Ok(Some(file.file_path.clone()));
+        }
+
+        if text != original {
+            journal.record(&file.file_path)?;
+            text.write(&file.file_path)?;
+        }
+
+        tx.send(ui::Event::Progress(1)).ok();
+        Ok(None)
+    }
+
+    /// Returns the file as it is on disk and with the transforms applied to the lines that are not filtered out.
+    fn transformed_file(
+        &self,
+        file: &FileResult,
+        decoder: &Decoder,
+    ) -> Result<(TextFile, TextFile)> {
+        let original = TextFile::read(&file.file_path, decoder)?;
+        let mut text = original.clone();
 
         for line in &file.lines {
             if let LineResult::Line(line) = line {
-                for m in line.transforms() {
-                    lines[line.line_no - 1] = m.apply(lines[line.line_no - 1].to_string());
+                if line.is_filtered() {
+                    continue;
+                }
+
+                if let Some(content) = text.line(line.line_no) {
+                    let content = line.apply_transforms(content);
+                    text.set_line(line.line_no, content);
                 }
             }
         }
 
-        let mut file = File::create(&file.file_path)?;
-        file.write_all(lines.join("\n").as_bytes())?;
-        tx.send(ui::Event::Progress(1)).map_err(anyhow::Error::from)
+        Ok((original, text))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::models::MatchResult;
+    use crate::models::{Captures, MatchResult, Search, SearchConfig};
 
     use super::*;
     use rstest::rstest;
     use std::{ops::Range, vec};
 
     #[rstest]
-    #[case(SearchResult {files: Vec::new(), conditions: Vec::new()}, SearchCondition::Exact("test".to_string()), Vec::new())]
-    #[case(SearchResult {
-             files: vec![FileResult {file_path: "test".to_string(),
-             lines: vec![LineResult::Line(Line::new(1, "test string".to_string(), Vec::new(), false))]}],
-             conditions: Vec::new()},
+    #[case(SearchResult::new(Vec::new(), Vec::new()), SearchCondition::Exact("test".to_string()), Vec::new())]
+    #[case(SearchResult::new(vec![FileResult {file_path: "test".to_string(), stamp: None,
+             lines: vec![LineResult::Line(Arc::new(Line::new(1, "test string".to_string(), Vec::new(), false)))]}],
+             Vec::new()),
              SearchCondition::Exact("test".to_string()),
-             vec![MatchResult::Found("test".to_string(), Range{start: 0, end: 4}, 1)])]
+             vec![MatchResult::Found("test".to_string(), Range{start: 0, end: 4}, 1, Captures::default())])]
     fn apply_test1(
         #[case] result: SearchResult,
         #[case] condition: SearchCondition,
@@ -270,7 +527,7 @@ mod tests {
                 .into_iter()
                 .flat_map(|file| file
                     .lines
-                    .into_iter()
+                    .iter()
                     .flat_map(|line| if let LineResult::Line(line) = line {
                         line.matches().clone()
                     } else {
@@ -283,28 +540,45 @@ mod tests {
     }
 
     #[rstest]
-    #[case(SearchResult {files: Vec::new(), conditions: Vec::new()}, SearchCondition::StartsWith("test".to_string()), None)]
-    #[case(SearchResult {
-             files: vec![FileResult {file_path: "test".to_string(),
+    #[case(SearchResult::new(Vec::new(), Vec::new()), SearchCondition::StartsWith("test".to_string()), None)]
+    #[case(SearchResult::new(vec![FileResult {file_path: "test".to_string(), stamp: None,
              lines: vec![
-                LineResult::Line(Line::new(1, "test string".to_string(), Vec::new(), false))
+                LineResult::Line(Arc::new(Line::new(1, "test string".to_string(), Vec::new(), false)))
              ]}],
-             conditions: Vec::new()},
+             Vec::new()),
              SearchCondition::LineStartsWith("te".to_string()), Some(Line::new(1, "test string".to_string(), Vec::new(), false)))]
-    #[case(SearchResult {
-             files: vec![FileResult {file_path: "test".to_string(),
+    #[case(SearchResult::new(vec![FileResult {file_path: "test".to_string(), stamp: None,
              lines: vec![
-                LineResult::Line(Line::new(1, "test string".to_string(), Vec::new(), false))
+                LineResult::Line(Arc::new(Line::new(1, "test string".to_string(), Vec::new(), false)))
              ]}],
-             conditions: Vec::new()},
+             Vec::new()),
              SearchCondition::LineEndsWith("st".to_string()), Some(Line::new(1, "test string".to_string(), Vec::new(), true)))]
-    #[case(SearchResult {
-             files: vec![FileResult {file_path: "test".to_string(),
+    #[case(SearchResult::new(vec![FileResult {file_path: "test".to_string(), stamp: None,
              lines: vec![
-                LineResult::Line(Line::new(1, "test string".to_string(), Vec::new(), false))
+                LineResult::Line(Arc::new(Line::new(1, "test string".to_string(), Vec::new(), false)))
              ]}],
-             conditions: Vec::new()},
+             Vec::new()),
              SearchCondition::LineInvertMatch("st".to_string()), Some(Line::new(1, "test string".to_string(), Vec::new(), true)))]
+    #[case(SearchResult::new(vec![FileResult {file_path: "test".to_string(), stamp: None,
+             lines: vec![
+                LineResult::Line(Arc::new(Line::new(1, "test string".to_string(), Vec::new(), false)))
+             ]}],
+             Vec::new()),
+             SearchCondition::And(Box::new(SearchCondition::LineStartsWith("te".to_string())),
+                                  Box::new(SearchCondition::Not(Box::new(SearchCondition::LineEndsWith("st".to_string()))))),
+             Some(Line::new(1, "test string".to_string(), Vec::new(), false)))]
+    #[case(SearchResult::new(vec![FileResult {file_path: "./src/main.rs".to_string(), stamp: None,
+             lines: vec![
+                LineResult::Line(Arc::new(Line::new(1, "test string".to_string(), Vec::new(), false)))
+             ]}],
+             Vec::new()),
+             SearchCondition::FileExt("rs".to_string()), Some(Line::new(1, "test string".to_string(), Vec::new(), false)))]
+    #[case(SearchResult::new(vec![FileResult {file_path: "./src/main.rs".to_string(), stamp: None,
+             lines: vec![
+                LineResult::Line(Arc::new(Line::new(1, "test string".to_string(), Vec::new(), false)))
+             ]}],
+             Vec::new()),
+             SearchCondition::FilePath("tests/**".to_string()), Some(Line::new(1, "test string".to_string(), Vec::new(), true)))]
     fn apply_line_filter_test(
         #[case] result: SearchResult,
         #[case] condition: SearchCondition,
@@ -315,12 +589,12 @@ mod tests {
                 .apply(condition, 1)
                 .files
                 .into_iter()
-                .flat_map(|file| file.lines)
+                .flat_map(|file| file.lines.clone())
                 .collect::<Vec<_>>()
                 .first()
                 .and_then(|it| {
                     if let LineResult::Line(line) = it {
-                        Some(line.clone())
+                        Some(Line::clone(line))
                     } else {
                         None
                     }
@@ -330,21 +604,19 @@ mod tests {
     }
 
     #[rstest]
-    #[case(SearchResult {files: Vec::new(), conditions: Vec::new()}, SearchCondition::StartsWith("test".to_string()), Vec::new())]
-    #[case(SearchResult {
-             files: vec![FileResult {file_path: "test".to_string(),
+    #[case(SearchResult::new(Vec::new(), Vec::new()), SearchCondition::StartsWith("test".to_string()), Vec::new())]
+    #[case(SearchResult::new(vec![FileResult {file_path: "test".to_string(), stamp: None,
              lines: vec![
-                LineResult::Line(Line::new(1, "test string".to_string(), vec![MatchResult::Found("test".to_string(), Range{start: 0, end: 4}, 1)], false))
+                LineResult::Line(Arc::new(Line::new(1, "test string".to_string(), vec![MatchResult::Found("test".to_string(), Range{start: 0, end: 4}, 1, Captures::default())], false)))
              ]}],
-             conditions: vec![SearchCondition::Exact("test".to_string())]},
-             SearchCondition::StartsWith("te".to_string()), vec![MatchResult::Filtered("test".to_string(), Range{start: 0, end: 4}, 1)])]
-    #[case(SearchResult {
-             files: vec![FileResult {file_path: "test".to_string(),
+             vec![SearchCondition::Exact("test".to_string())]),
+             SearchCondition::StartsWith("te".to_string()), vec![MatchResult::Filtered("test".to_string(), Range{start: 0, end: 4}, 1, Captures::default())])]
+    #[case(SearchResult::new(vec![FileResult {file_path: "test".to_string(), stamp: None,
              lines: vec![
-                LineResult::Line(Line::new(1, "test string".to_string(), vec![MatchResult::Found("test".to_string(), Range{start: 0, end: 4}, 1)], false))
+                LineResult::Line(Arc::new(Line::new(1, "test string".to_string(), vec![MatchResult::Found("test".to_string(), Range{start: 0, end: 4}, 1, Captures::default())], false)))
              ]}],
-             conditions: vec![SearchCondition::Exact("test".to_string())]},
-             SearchCondition::EndsWith("st".to_string()), vec![MatchResult::Filtered("test".to_string(), Range{start: 0, end: 4}, 1)])]
+             vec![SearchCondition::Exact("test".to_string())]),
+             SearchCondition::EndsWith("st".to_string()), vec![MatchResult::Filtered("test".to_string(), Range{start: 0, end: 4}, 1, Captures::default())])]
     fn apply_match_filter_test(
         #[case] result: SearchResult,
         #[case] condition: SearchCondition,
@@ -357,7 +629,7 @@ mod tests {
                 .into_iter()
                 .flat_map(|file| file
                     .lines
-                    .into_iter()
+                    .iter()
                     .flat_map(|line| if let LineResult::Line(line) = line {
                         line.matches().clone()
                     } else {
@@ -370,11 +642,11 @@ mod tests {
     }
 
     #[rstest]
-    #[case(vec![MatchResult::Transformed("transform".to_string(), Range{start: 0, end: 4}, 1)], "test_string".to_string(), "transform_string".to_string())]
-    #[case(vec![MatchResult::Transformed("transform".to_string(), Range{start: 0, end: 4}, 1),
-                MatchResult::Transformed("test".to_string(), Range{start: 0, end: 4}, 1)], "test_string".to_string(), "testsform_string".to_string())]
-    #[case(vec![MatchResult::Found("trqansform".to_string(), Range{start: 0, end: 4}, 1)], "test_string".to_string(), "test_string".to_string())]
-    #[case(vec![MatchResult::Filtered("trqansform".to_string(), Range{start: 0, end: 4}, 1)], "test_string".to_string(), "test_string".to_string())]
+    #[case(vec![MatchResult::Transformed("transform".to_string(), Range{start: 0, end: 4}, 1, Captures::default())], "test_string".to_string(), "transform_string".to_string())]
+    #[case(vec![MatchResult::Transformed("transform".to_string(), Range{start: 0, end: 4}, 1, Captures::default()),
+                MatchResult::Transformed("test".to_string(), Range{start: 0, end: 4}, 1, Captures::default())], "test_string".to_string(), "testsform_string".to_string())]
+    #[case(vec![MatchResult::Found("trqansform".to_string(), Range{start: 0, end: 4}, 1, Captures::default())], "test_string".to_string(), "test_string".to_string())]
+    #[case(vec![MatchResult::Filtered("trqansform".to_string(), Range{start: 0, end: 4}, 1, Captures::default())], "test_string".to_string(), "test_string".to_string())]
     fn apply_match_test(
         #[case] m: Vec<MatchResult>,
         #[case] text: String,
@@ -382,4 +654,324 @@ mod tests {
     ) {
         assert_eq!(m.iter().fold(text, |acc, x| x.apply(acc)), expected)
     }
+
+    #[rstest]
+    #[case(vec![("a", 1), ("b", 2)], vec![("a", vec![1]), ("b", vec![2])])]
+    #[case(vec![("a", 2)], vec![("a", vec![2])])]
+    #[case(vec![("c", 1)], vec![])]
+    fn test_retain_marked(
+        #[case] marks: Vec<(&str, usize)>,
+        #[case] expected: Vec<(&str, Vec<usize>)>,
+    ) {
+        let file = |path: &str, line_nos: Vec<usize>| FileResult {
+            file_path: path.to_string(),
+            stamp: None,
+            lines: line_nos
+                .into_iter()
+                .map(|n| {
+                    LineResult::Line(Arc::new(Line::new(
+                        n,
+                        "test".to_string(),
+                        Vec::new(),
+                        false,
+                    )))
+                })
+                .collect(),
+        };
+        let result = SearchResult::new(vec![file("a", vec![1, 2]), file("b", vec![2])], Vec::new());
+
+        assert_eq!(
+            result
+                .retain_marked(&marks.into_iter().map(|(p, n)| (p.to_string(), n)).collect())
+                .files,
+            expected
+                .into_iter()
+                .map(|(p, n)| Arc::new(file(p, n)))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        let file = |path: &str| FileResult {
+            file_path: path.to_string(),
+            stamp: None,
+            lines: vec![LineResult::Line(Arc::new(Line::new(
+                1,
+                "foo bar".to_string(),
+                vec![MatchResult::Found(
+                    "foo".to_string(),
+                    Range { start: 0, end: 3 },
+                    1,
+                    Captures::default(),
+                )],
+                false,
+            )))],
+        };
+        let conditions = vec![SearchCondition::Exact("foo".to_string())];
+        let apply = |result: SearchResult| {
+            result
+                .apply(SearchCondition::UpperCase, 2)
+                .apply(SearchCondition::Exact("bar".to_string()), 3)
+        };
+        let searched = SearchResult::new(vec![file("a"), file("b")], conditions.clone());
+        let parts = ["b", "a"]
+            .into_iter()
+            .map(|path| apply(SearchResult::new(vec![file(path)], conditions.clone())))
+            .collect();
+
+        let mut merged = searched.clone().merge(parts);
+        let mut applied = apply(searched);
+
+        assert_eq!(merged.files, applied.files);
+        assert_eq!(
+            merged.to_conditions_string(),
+            applied.to_conditions_string()
+        );
+        assert_eq!(
+            merged.delete_last_condition().files,
+            applied.delete_last_condition().files
+        );
+    }
+
+    #[rstest]
+    #[case(0, vec![])]
+    #[case(1, vec![SearchCondition::Exact("foo".to_string())])]
+    #[case(2, vec![SearchCondition::Exact("foo".to_string()), SearchCondition::FileExt("rs".to_string())])]
+    fn test_delete_last_condition(
+        #[case] start_index: usize,
+        #[case] conditions: Vec<SearchCondition>,
+    ) {
+        let file = FileResult {
+            file_path: "a.rs".to_string(),
+            stamp: None,
+            lines: ["foo bar", "baz", "foo baz"]
+                .iter()
+                .enumerate()
+                .map(|(i, text)| {
+                    LineResult::Line(Arc::new(Line::new(
+                        i + 1,
+                        text.to_string(),
+                        Vec::new(),
+                        false,
+                    )))
+                })
+                .collect(),
+        };
+        // The conditions before `start_index` are used by the search, so there are no snapshots of them.
+        let searched = SearchResult {
+            snapshots: Vec::new(),
+            ..SearchResult::new(vec![file], conditions[..start_index].to_vec()).reapply()
+        };
+        let applied = conditions[start_index..]
+            .iter()
+            .enumerate()
+            .fold(searched, |r, (i, c)| {
+                r.apply(c.clone(), start_index + i + 1)
+            });
+        let mut result = applied
+            .apply(
+                SearchCondition::Exact("bar".to_string()),
+                conditions.len() + 1,
+            )
+            .apply(SearchCondition::UpperCase, conditions.len() + 2);
+
+        let deleted = result.delete_last_condition();
+        assert_eq!(
+            deleted.files,
+            applied
+                .apply(
+                    SearchCondition::Exact("bar".to_string()),
+                    conditions.len() + 1
+                )
+                .files
+        );
+        assert_eq!(deleted.conditions.len(), conditions.len() + 1);
+
+        let deleted = result.delete_last_condition();
+        assert_eq!(deleted.files, applied.files);
+        assert_eq!(deleted.files, deleted.reapply().files);
+        assert_eq!(deleted.conditions, conditions);
+    }
+
+    #[test]
+    fn test_reflect_keeps_line_endings() {
+        let path = std::env::temp_dir().join(format!("igr-reflect-{}.txt", std::process::id()));
+        std::fs::write(&path, "\u{feff}foo bar\r\nbaz foo\r\nend").unwrap();
+        let file_path = path.to_str().unwrap().to_string();
+        let result = Search::new(vec![file_path.clone()], None)
+            .search(
+                SearchConfig::default(),
+                vec![SearchCondition::Exact("foo".to_string())],
+            )
+            .unwrap()
+            .apply(SearchCondition::UpperCase, 2);
+        let (tx, _rx) = mpsc::channel();
+        let root = std::env::temp_dir().join(format!("igr-reflect-{}", std::process::id()));
+        let journal = UndoJournal::begin(&root).unwrap();
+
+        result.reflect(tx, &journal, &Decoder::default()).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "\u{feff}FOO bar\r\nbaz FOO\r\nend"
+        );
+        assert_eq!(
+            UndoJournal::undo(&root).unwrap(),
+            vec![std::fs::canonicalize(&path).unwrap().display().to_string()]
+        );
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "\u{feff}foo bar\r\nbaz foo\r\nend"
+        );
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_reflect_transforms_of_other_lengths() {
+        let path = std::env::temp_dir().join(format!("igr-lengths-{}.txt", std::process::id()));
+        std::fs::write(&path, "foo foo\r\nfoo\r\n").unwrap();
+        let file_path = path.to_str().unwrap().to_string();
+        let result = Search::new(vec![file_path.clone()], None)
+            .search(
+                SearchConfig::default(),
+                vec![SearchCondition::Exact("foo".to_string())],
+            )
+            .unwrap()
+            .apply(SearchCondition::Update("barbaz".to_string()), 2);
+        let (tx, _rx) = mpsc::channel();
+        let root = std::env::temp_dir().join(format!("igr-lengths-{}", std::process::id()));
+        let journal = UndoJournal::begin(&root).unwrap();
+
+        assert!(result
+            .diff(&Decoder::default())
+            .unwrap()
+            .contains("+barbaz barbaz\r\n"));
+        result.reflect(tx, &journal, &Decoder::default()).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "barbaz barbaz\r\nbarbaz\r\n"
+        );
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_reflect_skips_filtered_lines() {
+        let path = std::env::temp_dir().join(format!("igr-filtered-{}.txt", std::process::id()));
+        std::fs::write(&path, "foo bar\nfoo baz\n").unwrap();
+        let file_path = path.to_str().unwrap().to_string();
+        let result = Search::new(vec![file_path.clone()], None)
+            .search(
+                SearchConfig::default(),
+                vec![SearchCondition::Exact("foo".to_string())],
+            )
+            .unwrap()
+            .apply(SearchCondition::UpperCase, 2)
+            .apply(SearchCondition::LineContains("baz".to_string()), 3);
+        let (tx, _rx) = mpsc::channel();
+        let root = std::env::temp_dir().join(format!("igr-filtered-{}", std::process::id()));
+        let journal = UndoJournal::begin(&root).unwrap();
+
+        assert!(!result
+            .diff(&Decoder::default())
+            .unwrap()
+            .contains("FOO bar"));
+        result.reflect(tx, &journal, &Decoder::default()).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "foo bar\nFOO baz\n"
+        );
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_reflect_keeps_encoding() {
+        let path = std::env::temp_dir().join(format!("igr-encoding-{}.txt", std::process::id()));
+        let utf16 = |s: &str| {
+            s.encode_utf16()
+                .flat_map(u16::to_le_bytes)
+                .collect::<Vec<_>>()
+        };
+        std::fs::write(&path, utf16("\u{feff}foo bar\r\n")).unwrap();
+        let file_path = path.to_str().unwrap().to_string();
+        let result = Search::new(vec![file_path.clone()], None)
+            .search(
+                SearchConfig::default(),
+                vec![SearchCondition::Exact("foo".to_string())],
+            )
+            .unwrap()
+            .apply(SearchCondition::UpperCase, 2);
+        let (tx, _rx) = mpsc::channel();
+        let root = std::env::temp_dir().join(format!("igr-encoding-{}", std::process::id()));
+        let journal = UndoJournal::begin(&root).unwrap();
+
+        result.reflect(tx, &journal, &Decoder::default()).unwrap();
+
+        assert_eq!(std::fs::read(&path).unwrap(), utf16("\u{feff}FOO bar\r\n"));
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_search_skips_binary_and_undecodable_files() {
+        let dir = std::env::temp_dir().join(format!("igr-skipped-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.txt"), "foo").unwrap();
+        std::fs::write(dir.join("b.bin"), b"foo\x00").unwrap();
+        std::fs::write(dir.join("c.txt"), b"foo caf\xe9").unwrap();
+        let result = Search::new(vec![dir.to_str().unwrap().to_string()], None)
+            .search(
+                SearchConfig::default(),
+                vec![SearchCondition::Exact("foo".to_string())],
+            )
+            .unwrap();
+
+        assert_eq!(
+            result.stat(),
+            Stat {
+                file_count: 1,
+                match_count: 1,
+                binary_count: 1,
+                undecodable_count: 1,
+                too_large_count: 0,
+            }
+        );
+        assert_eq!(
+            result.stat().skipped_summary(),
+            Some("1 binary file, 1 undecodable file".to_string())
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_reflect_skips_modified_files() {
+        let path = std::env::temp_dir().join(format!("igr-modified-{}.txt", std::process::id()));
+        std::fs::write(&path, "foo bar").unwrap();
+        let file_path = path.to_str().unwrap().to_string();
+        let result = Search::new(vec![file_path.clone()], None)
+            .search(
+                SearchConfig::default(),
+                vec![SearchCondition::Exact("foo".to_string())],
+            )
+            .unwrap()
+            .apply(SearchCondition::UpperCase, 2);
+        let (tx, _rx) = mpsc::channel();
+        let root = std::env::temp_dir().join(format!("igr-modified-{}", std::process::id()));
+        let journal = UndoJournal::begin(&root).unwrap();
+
+        std::fs::write(&path, "baz\nfoo bar").unwrap();
+
+        assert_eq!(
+            result.reflect(tx, &journal, &Decoder::default()).unwrap(),
+            vec![file_path]
+        );
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "baz\nfoo bar");
+        assert!(!root.exists());
+        std::fs::remove_file(&path).unwrap();
+    }
 }
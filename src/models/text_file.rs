@@ -0,0 +1,203 @@
+use super::decoder::{self, Decoder};
+use super::line_reader;
+use anyhow::{anyhow, Result};
+use encoding_rs::{Encoding, UTF_8};
+use itertools::Itertools;
+use similar::TextDiff;
+use std::fmt::{self, Display, Formatter};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+pub const BOM: char = '\u{feff}';
+
+/// File content split into lines, keeping the BOM, the terminator of each line
+/// and the encoding so that it can be written back byte for byte.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextFile {
+    bom: bool,
+    lines: Vec<(String, String)>,
+    encoding: &'static Encoding,
+}
+
+impl TextFile {
+    pub fn new(text: &str) -> Self {
+        Self::with_encoding(text, UTF_8)
+    }
+
+    pub fn with_encoding(text: &str, encoding: &'static Encoding) -> Self {
+        let (bom, text) = match text.strip_prefix(BOM) {
+            Some(text) => (true, text),
+            None => (false, text),
+        };
+
+        Self {
+            bom,
+            lines: text
+                .split_inclusive('\n')
+                .map(|line| {
+                    let content = line_reader::trim_terminator(line);
+                    (content.to_string(), line[content.len()..].to_string())
+                })
+                .collect_vec(),
+            encoding,
+        }
+    }
+
+    pub fn read(path: &str, decoder: &Decoder) -> Result<Self> {
+        let (text, encoding) = decoder.read(path)?;
+        Ok(Self::with_encoding(&text, encoding))
+    }
+
+    /// Writes the file back in the encoding it was read in.
+    pub fn write(&self, path: &str) -> Result<()> {
+        write_atomic(
+            Path::new(path),
+            &decoder::encode(&self.to_string(), self.encoding)?,
+        )
+    }
+
+    /// Renders the changes to `other` as a unified diff that `git apply` accepts.
+    pub fn diff(&self, other: &TextFile, path: &str) -> String {
+        let (old, new) = (self.to_string(), other.to_string());
+
+        if old == new {
+            return String::new();
+        }
+
+        let path = path.trim_start_matches("./");
+        format!(
+            "diff --git a/{path} b/{path}\n{}",
+            TextDiff::from_lines(&old, &new)
+                .unified_diff()
+                .header(&format!("a/{}", path), &format!("b/{}", path))
+        )
+    }
+
+    /// Returns the line without its terminator, `line_no` starting at 1.
+    pub fn line(&self, line_no: usize) -> Option<&str> {
+        line_no
+            .checked_sub(1)
+            .and_then(|i| self.lines.get(i))
+            .map(|(content, _)| content.as_str())
+    }
+
+    pub fn set_line(&mut self, line_no: usize, content: String) {
+        if let Some(line) = line_no.checked_sub(1).and_then(|i| self.lines.get_mut(i)) {
+            line.0 = content;
+        }
+    }
+}
+
+/// Writes to a temporary file next to `path` and renames it into place,
+/// keeping the permissions of the file being replaced. A symlink is followed so that its target is written.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let path = &fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("Invalid file path: {}", path.display()))?;
+    let tmp = path.with_file_name(format!(
+        ".{}.igr-{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+    let result = File::create(&tmp)
+        .and_then(|mut file| {
+            file.write_all(contents)?;
+            file.sync_all()
+        })
+        .and_then(|_| match fs::metadata(path) {
+            Ok(metadata) => fs::set_permissions(&tmp, metadata.permissions()),
+            Err(_) => Ok(()),
+        })
+        .and_then(|_| fs::rename(&tmp, path));
+
+    if result.is_err() {
+        fs::remove_file(&tmp).ok();
+    }
+
+    Ok(result?)
+}
+
+impl Display for TextFile {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.bom {
+            write!(f, "{}", BOM)?;
+        }
+
+        self.lines
+            .iter()
+            .try_for_each(|(content, terminator)| write!(f, "{}{}", content, terminator))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("a\nb\n")]
+    #[case("a\r\nb\r\n")]
+    #[case("a\r\nb\nc")]
+    #[case("\u{feff}a\r\n")]
+    #[case("a\n\n\n")]
+    #[case("")]
+    fn test_round_trip(#[case] text: &str) {
+        assert_eq!(TextFile::new(text).to_string(), text);
+    }
+
+    #[rstest]
+    #[case("\u{feff}foo\r\nbar\r\n", 1, "\u{feff}baz\r\nbar\r\n")]
+    #[case("foo\r\nbar", 2, "foo\r\nbaz")]
+    #[case("foo\nbar\n", 3, "foo\nbar\n")]
+    fn test_set_line(#[case] text: &str, #[case] line_no: usize, #[case] expected: &str) {
+        let mut file = TextFile::new(text);
+        file.set_line(line_no, "baz".to_string());
+        assert_eq!(file.to_string(), expected);
+    }
+
+    #[test]
+    fn test_line() {
+        let file = TextFile::new("\u{feff}foo\r\nbar");
+        assert_eq!(file.line(1), Some("foo"));
+        assert_eq!(file.line(2), Some("bar"));
+        assert_eq!(file.line(0), None);
+    }
+
+    #[rstest]
+    #[case("foo\nbar\n", "baz", "./src/a.rs", "diff --git a/src/a.rs b/src/a.rs\n--- a/src/a.rs\n+++ b/src/a.rs\n@@ -1,2 +1,2 @@\n-foo\n+baz\n bar\n")]
+    #[case("foo", "baz", "a.rs", "diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@ -1 +1 @@\n-foo\n\\ No newline at end of file\n+baz\n\\ No newline at end of file\n")]
+    #[case("bar\n", "bar", "a.rs", "")]
+    fn test_diff(
+        #[case] text: &str,
+        #[case] line: &str,
+        #[case] path: &str,
+        #[case] expected: &str,
+    ) {
+        let file = TextFile::new(text);
+        let mut other = file.clone();
+        other.set_line(1, line.to_string());
+        assert_eq!(file.diff(&other, path), expected);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_atomic_keeps_symlink() {
+        let dir = std::env::temp_dir().join(format!("igr-symlink-{}", std::process::id()));
+        let target = dir.join("target.txt");
+        let link = dir.join("link.txt");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&target, "foo").unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        write_atomic(&link, b"bar").unwrap();
+
+        assert!(fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(fs::read_to_string(&target).unwrap(), "bar");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
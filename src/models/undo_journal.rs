@@ -0,0 +1,127 @@
+use super::config::state_dir;
+use super::text_file::write_atomic;
+use anyhow::{anyhow, Result};
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const UNDO_DIR: &str = "undo";
+const MANIFEST: &str = "manifest";
+
+/// Backups of the files changed by one replace run, stored under `<dir>/<run>`
+/// together with a manifest of `<backup>\t<path>` lines.
+/// The run directory is only created when the first file is recorded.
+#[derive(Debug)]
+pub struct UndoJournal {
+    dir: PathBuf,
+    manifest: Mutex<Option<File>>,
+    count: AtomicUsize,
+}
+
+impl UndoJournal {
+    /// The journal of the current directory, `$XDG_STATE_HOME/igr/undo/<directory>`,
+    /// kept outside the tree so that it is never searched or replaced.
+    pub fn dir() -> Result<PathBuf> {
+        let cwd = fs::canonicalize(env::current_dir()?)?;
+        let name = cwd
+            .to_string_lossy()
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '-' })
+            .collect::<String>();
+
+        state_dir()
+            .map(|dir| dir.join(UNDO_DIR).join(name))
+            .ok_or_else(|| anyhow!("Cannot find a directory for the undo journal"))
+    }
+
+    pub fn begin(dir: &Path) -> Result<Self> {
+        let run = SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos();
+
+        Ok(Self {
+            dir: dir.join(format!("{:020}", run)),
+            manifest: Mutex::new(None),
+            count: AtomicUsize::new(0),
+        })
+    }
+
+    /// Saves the current contents of the file before it is overwritten.
+    pub fn record(&self, path: &str) -> Result<()> {
+        let mut manifest = self
+            .manifest
+            .lock()
+            .map_err(|_| anyhow!("Undo journal is poisoned"))?;
+
+        if manifest.is_none() {
+            fs::create_dir_all(&self.dir)?;
+            *manifest = Some(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(self.dir.join(MANIFEST))?,
+            );
+        }
+
+        let backup = self.count.fetch_add(1, Ordering::Relaxed).to_string();
+
+        fs::copy(path, self.dir.join(&backup))?;
+        writeln!(
+            manifest.as_mut().unwrap(),
+            "{}\t{}",
+            backup,
+            fs::canonicalize(path)?.display()
+        )?;
+
+        Ok(())
+    }
+
+    /// Restores the files changed by the latest run and removes the run from the journal.
+    pub fn undo(dir: &Path) -> Result<Vec<String>> {
+        let dir = fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_dir())
+            .max()
+            .ok_or_else(|| anyhow!("Nothing to undo"))?;
+
+        let restored = fs::read_to_string(dir.join(MANIFEST))?
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .map(|(backup, path)| {
+                write_atomic(Path::new(path), &fs::read(dir.join(backup))?)?;
+                Ok(path.to_string())
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        fs::remove_dir_all(&dir)?;
+        Ok(restored)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_undo() {
+        let root = std::env::temp_dir().join(format!("igr-undo-{}", std::process::id()));
+        let path = root.join("test.txt");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(&path, "first").unwrap();
+
+        let journal = UndoJournal::begin(&root.join("undo")).unwrap();
+        assert!(!root.join("undo").exists());
+        journal.record(path.to_str().unwrap()).unwrap();
+        fs::write(&path, "second").unwrap();
+
+        assert_eq!(UndoJournal::undo(&root.join("undo")).unwrap().len(), 1);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "first");
+        assert!(UndoJournal::undo(&root.join("undo")).is_err());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
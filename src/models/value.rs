@@ -2,6 +2,8 @@ use std::fmt::{self, Display, Formatter};
 
 use anyhow::{anyhow, Result};
 
+use crate::parser::quote;
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum Value {
     Str(String),
@@ -49,7 +51,7 @@ impl Value {
 impl Display for Value {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         let s = match self {
-            Value::Str(s) => s.to_string(),
+            Value::Str(s) => quote(s),
             Value::Num(n) => n.to_string(),
             Value::Env(e) => format!("env.{}", e),
         };
@@ -0,0 +1,134 @@
+use super::SearchConfig;
+use anyhow::{anyhow, Result};
+use ignore::overrides::{Override, OverrideBuilder};
+use ignore::types::{Types, TypesBuilder};
+use ignore::WalkBuilder;
+use itertools::Itertools;
+
+/// The globs and file types that select the files to search, in the style of ripgrep's
+/// `--glob`, `--iglob`, `--type` and `--type-not`. Globs starting with `!` exclude files.
+#[derive(Clone, Debug)]
+pub struct WalkFilter {
+    overrides: Override,
+    types: Types,
+}
+
+impl WalkFilter {
+    pub fn new(config: &SearchConfig) -> Result<Self> {
+        let mut overrides = OverrideBuilder::new(".");
+
+        for glob in &config.globs {
+            overrides
+                .add(glob)
+                .map_err(|e| anyhow!("Invalid glob `{}`: {}", glob, e))?;
+        }
+
+        overrides.case_insensitive(true)?;
+
+        for glob in &config.iglobs {
+            overrides
+                .add(glob)
+                .map_err(|e| anyhow!("Invalid glob `{}`: {}", glob, e))?;
+        }
+
+        overrides.case_insensitive(false)?;
+
+        if let Some(exclude) = &config.exclude_path {
+            overrides
+                .add(&format!("!{}", exclude))
+                .map_err(|e| anyhow!("Invalid exclude path `{}`: {}", exclude, e))?;
+        }
+
+        let mut types = TypesBuilder::new();
+
+        types.add_defaults();
+        config.types.iter().for_each(|t| {
+            types.select(t);
+        });
+        config.types_not.iter().for_each(|t| {
+            types.negate(t);
+        });
+
+        Ok(Self {
+            overrides: overrides.build()?,
+            types: types.build()?,
+        })
+    }
+
+    pub fn apply(&self, builder: &mut WalkBuilder) {
+        builder
+            .overrides(self.overrides.clone())
+            .types(self.types.clone());
+    }
+
+    /// The built-in file types and their globs, as listed by `--type-list`.
+    pub fn type_list() -> Vec<String> {
+        let mut types = TypesBuilder::new();
+
+        types.add_defaults();
+        types
+            .definitions()
+            .iter()
+            .map(|def| format!("{}: {}", def.name(), def.globs().join(", ")))
+            .collect_vec()
+    }
+
+    #[cfg(test)]
+    fn is_match(&self, path: &str) -> bool {
+        !self.overrides.matched(path, false).is_ignore()
+            && !self.types.matched(path, false).is_ignore()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(vec![], vec![], vec![], vec![], "src/main.rs", true)]
+    #[case(vec!["*.rs"], vec![], vec![], vec![], "src/main.rs", true)]
+    #[case(vec!["*.rs"], vec![], vec![], vec![], "README.md", false)]
+    #[case(vec!["!*.rs"], vec![], vec![], vec![], "src/main.rs", false)]
+    #[case(vec!["*.md"], vec![], vec![], vec![], "README.MD", false)]
+    #[case(vec![], vec!["*.md"], vec![], vec![], "README.MD", true)]
+    #[case(vec![], vec![], vec!["rust"], vec![], "src/main.rs", true)]
+    #[case(vec![], vec![], vec!["rust"], vec![], "index.js", false)]
+    #[case(vec![], vec![], vec![], vec!["js"], "index.js", false)]
+    #[case(vec![], vec![], vec![], vec!["js"], "src/main.rs", true)]
+    fn test_is_match(
+        #[case] globs: Vec<&str>,
+        #[case] iglobs: Vec<&str>,
+        #[case] types: Vec<&str>,
+        #[case] types_not: Vec<&str>,
+        #[case] path: &str,
+        #[case] expected: bool,
+    ) {
+        let to_strings = |v: Vec<&str>| v.into_iter().map(String::from).collect_vec();
+        let filter = WalkFilter::new(&SearchConfig {
+            globs: to_strings(globs),
+            iglobs: to_strings(iglobs),
+            types: to_strings(types),
+            types_not: to_strings(types_not),
+            ..SearchConfig::default()
+        })
+        .unwrap();
+
+        assert_eq!(filter.is_match(path), expected);
+    }
+
+    #[rstest]
+    #[case(vec!["{a"], vec![], "Invalid glob `{a`")]
+    #[case(vec![], vec!["foo"], "unrecognized file type: foo")]
+    fn test_new_error(#[case] globs: Vec<&str>, #[case] types: Vec<&str>, #[case] expected: &str) {
+        let error = WalkFilter::new(&SearchConfig {
+            globs: globs.into_iter().map(String::from).collect(),
+            types: types.into_iter().map(String::from).collect(),
+            ..SearchConfig::default()
+        })
+        .unwrap_err()
+        .to_string();
+
+        assert!(error.starts_with(expected), "{}", error);
+    }
+}
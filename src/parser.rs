@@ -1,18 +1,19 @@
 use crate::models::search_condition::{
-    CAMEL_CASE, CONSTANT, CONTAINS, DELETE, ENDS_WITH, IGNORE_CASE, INSERT, INVERT_MATCH,
-    INVERT_MATCH_REGEX, KEBAB_CASE, LINE_BYTE_LENGTH, LINE_CONTAINS, LINE_ENDS_WITH,
-    LINE_INVERT_MATCH, LINE_INVERT_MATCH_REGEX, LINE_LENGTH, LINE_REGEX, LINE_STARTS_WITH,
-    LOWER_CASE, NUMBER, REGEX, REPLACE, SNAKE_CASE, STARTS_WITH, TRIM, TRIM_END, TRIM_START,
-    UPDATE, UPPER_CAMEL_CASE, UPPER_CASE, UPPER_KEBAB_CASE, UPPER_SNAKE_CASE, WHOLE_WORD,
+    AND, CAMEL_CASE, CONSTANT, CONTAINS, DELETE, ENDS_WITH, FILE_EXT, FILE_PATH, IGNORE_CASE,
+    INSERT, INVERT_MATCH, INVERT_MATCH_REGEX, KEBAB_CASE, LINE_BYTE_LENGTH, LINE_CONTAINS,
+    LINE_ENDS_WITH, LINE_INVERT_MATCH, LINE_INVERT_MATCH_REGEX, LINE_LENGTH, LINE_REGEX,
+    LINE_STARTS_WITH, LOWER_CASE, NOT, NUMBER, OR, REGEX, REGEX_REPLACE, REPLACE, SNAKE_CASE,
+    STARTS_WITH, TRIM, TRIM_END, TRIM_START, UPDATE, UPPER_CAMEL_CASE, UPPER_CASE,
+    UPPER_KEBAB_CASE, UPPER_SNAKE_CASE, WHOLE_WORD,
 };
 use crate::models::value::Op;
 use crate::models::{SearchCondition, Value};
 use anyhow::Result;
 use nom::branch::alt;
 use nom::bytes::complete::take_while1;
-use nom::character::complete::{char, digit1, space0};
-use nom::combinator::{eof, rest};
-use nom::multi::many0;
+use nom::character::complete::{anychar, char, digit1, space0};
+use nom::combinator::{eof, map, not, opt, peek, recognize, rest};
+use nom::multi::{fold_many0, many0, many1, separated_list0};
 use nom::sequence::{delimited, preceded};
 use nom::{
     bytes::complete::{is_not, tag},
@@ -21,32 +22,178 @@ use nom::{
     IResult,
 };
 
-pub fn parse(s: &str) -> Result<Vec<SearchCondition>> {
-    match many0(any_condition)(s) {
-        Ok(r) => r.1.into_iter().collect(),
-        Err(_) => Ok(vec![]),
+mod alias;
+mod diagnostic;
+
+pub use self::alias::Aliases;
+pub use self::diagnostic::ParseError;
+
+const MAX_ALIAS_DEPTH: usize = 16;
+
+/// Parses a query, expanding saved queries and aliases in `aliases`.
+pub fn parse(s: &str, aliases: &Aliases) -> Result<Vec<SearchCondition>> {
+    parse_expanded(s, aliases, 0)
+}
+
+fn parse_expanded(s: &str, aliases: &Aliases, depth: usize) -> Result<Vec<SearchCondition>> {
+    let query = s.trim_end();
+    let mut input = query;
+    let mut conditions = Vec::new();
+
+    while !input.trim().is_empty() {
+        if let Some((rest, expanded)) = aliases.invocation(input) {
+            let offset = query.len() - input.trim_start().len();
+            let stage = input[..input.len() - rest.len()].trim();
+            let stage = stage.strip_suffix('|').unwrap_or(stage).trim_end();
+            let error = |message: String| ParseError::new(offset, stage, &message);
+
+            if depth >= MAX_ALIAS_DEPTH {
+                return Err(error("alias expands recursively".to_string()).into());
+            }
+
+            let expanded = expanded.map_err(|e| error(e.to_string()))?;
+            conditions.extend(parse_expanded(&expanded, aliases, depth + 1).map_err(
+                |e| match depth {
+                    0 => error(format!("invalid alias ({})", e)).into(),
+                    _ => e,
+                },
+            )?);
+            input = rest;
+        } else if let Some(name) = aliases.unknown_query(input) {
+            return Err(diagnostic::unknown_query(
+                query,
+                input,
+                name,
+                aliases.queries().keys().map(String::as_str),
+            )
+            .into());
+        } else {
+            let (rest, condition) = stage(query, input)?;
+            conditions.push(condition);
+            input = rest;
+        }
+    }
+
+    Ok(conditions)
+}
+
+/// Parses a single condition, failing if anything follows it.
+pub fn parse_condition(s: &str) -> Result<SearchCondition> {
+    let query = s.trim_end();
+    let (rest, condition) = stage(query, query)?;
+
+    if rest.trim().is_empty() {
+        Ok(condition)
+    } else {
+        Err(
+            ParseError::new(query.len() - rest.len(), rest.trim(), "unexpected input")
+                .expected("a single condition")
+                .into(),
+        )
+    }
+}
+
+fn stage<'a>(query: &str, input: &'a str) -> Result<(&'a str, SearchCondition), ParseError> {
+    match any_condition(input) {
+        Ok((rest, condition)) if rest.len() < input.len() => {
+            let stage = input[..input.len() - rest.len()].trim();
+            let stage = stage.strip_suffix('|').unwrap_or(stage).trim_end();
+            let condition = condition.map_err(|e| diagnostic::invalid(query, input, stage, e))?;
+
+            diagnostic::verify(query, stage, &condition, false)?;
+            Ok((rest, condition))
+        }
+        _ => Err(diagnostic::unexpected(query, input)),
     }
 }
 
 pub fn any_condition(s: &str) -> IResult<&str, Result<SearchCondition>> {
+    or_condition(s, false)
+}
+
+fn or_condition(s: &str, in_group: bool) -> IResult<&str, Result<SearchCondition>> {
+    tuple((
+        |s| and_condition(s, in_group),
+        many0(preceded(or_operator, |s| and_condition(s, in_group))),
+    ))(s)
+    .map(|(s, (lhs, rest))| {
+        (
+            s,
+            rest.into_iter().fold(lhs, |acc, rhs| {
+                acc.and_then(|l| rhs.and_then(|r| SearchCondition::new_or(l, r)))
+            }),
+        )
+    })
+}
+
+fn and_condition(s: &str, in_group: bool) -> IResult<&str, Result<SearchCondition>> {
+    tuple((
+        |s| unary_condition(s, in_group),
+        many0(preceded(and_operator, |s| unary_condition(s, in_group))),
+    ))(s)
+    .map(|(s, (lhs, rest))| {
+        (
+            s,
+            rest.into_iter().fold(lhs, |acc, rhs| {
+                acc.and_then(|l| rhs.and_then(|r| SearchCondition::new_and(l, r)))
+            }),
+        )
+    })
+}
+
+fn unary_condition(s: &str, in_group: bool) -> IResult<&str, Result<SearchCondition>> {
+    alt((
+        |s| not_condition(s, in_group),
+        group_condition,
+        |s| single_condition(s, in_group),
+    ))(s)
+}
+
+fn not_condition(s: &str, in_group: bool) -> IResult<&str, Result<SearchCondition>> {
+    preceded(tuple((space0, tag(NOT))), |s| unary_condition(s, in_group))(s)
+        .map(|(s, c)| (s, c.and_then(SearchCondition::new_not)))
+}
+
+fn group_condition(s: &str) -> IResult<&str, Result<SearchCondition>> {
+    tuple((
+        space0,
+        char('('),
+        |s| or_condition(s, true),
+        space0,
+        char(')'),
+        separator,
+    ))(s)
+    .map(|(_, (_, _, c, _, _, s))| (s.trim(), c))
+}
+
+fn single_condition(s: &str, in_group: bool) -> IResult<&str, Result<SearchCondition>> {
     alt((
         matcher_string,
         any_matcher,
         any_filter,
         any_line_filter,
+        any_file_filter,
         any_transform,
-        exact,
+        |s| exact(s, in_group),
     ))(s)
 }
 
+fn and_operator(s: &str) -> IResult<&str, &str> {
+    delimited(space0, tag(AND), space0)(s)
+}
+
+fn or_operator(s: &str) -> IResult<&str, &str> {
+    delimited(space0, tag(OR), space0)(s)
+}
+
 fn any_matcher(s: &str) -> IResult<&str, Result<SearchCondition>> {
     alt((
         number_eq,
         number_ne,
-        number_gt,
         number_gte,
-        number_lt,
+        number_gt,
         number_lte,
+        number_lt,
         number,
         ignore_case,
         whole_word,
@@ -74,22 +221,27 @@ fn any_line_filter(s: &str) -> IResult<&str, Result<SearchCondition>> {
         line_invert_match_regex,
         line_length_eq,
         line_length_ne,
-        line_length_gt,
         line_length_gte,
-        line_length_lt,
+        line_length_gt,
         line_length_lte,
+        line_length_lt,
         line_bytelength_eq,
         line_bytelength_ne,
-        line_bytelength_gt,
         line_bytelength_gte,
-        line_bytelength_lt,
+        line_bytelength_gt,
         line_bytelength_lte,
+        line_bytelength_lt,
     ))(s)
 }
 
+fn any_file_filter(s: &str) -> IResult<&str, Result<SearchCondition>> {
+    alt((file_ext, file_path))(s)
+}
+
 fn any_transform(s: &str) -> IResult<&str, Result<SearchCondition>> {
     alt((
         replace,
+        regex_replace,
         insert,
         update,
         delete,
@@ -112,8 +264,115 @@ fn string(s: &str) -> IResult<&str, &str> {
     verify(is_not(")"), |s: &str| !s.is_empty())(s.trim())
 }
 
+/// A quoted string literal. `\\`, `\n`, `\t` and the quote characters are unescaped,
+/// any other escape sequence is kept as is so that regex escapes can be written without doubling.
+fn quoted<'a>(quote: char) -> impl FnMut(&'a str) -> IResult<&'a str, String> {
+    delimited(
+        char(quote),
+        fold_many0(
+            alt((
+                map(
+                    is_not(if quote == '"' { "\"\\" } else { "'\\" }),
+                    str::to_string,
+                ),
+                preceded(
+                    char('\\'),
+                    alt((
+                        value("\\".to_string(), char('\\')),
+                        value("\"".to_string(), char('"')),
+                        value("'".to_string(), char('\'')),
+                        value("\n".to_string(), char('n')),
+                        value("\t".to_string(), char('t')),
+                        map(anychar, |c| format!("\\{}", c)),
+                    )),
+                ),
+            )),
+            String::new,
+            |acc, s| acc + &s,
+        ),
+        char(quote),
+    )
+}
+
+/// A condition argument, either a double quoted string literal or the trimmed text up to one of `terminators`.
+fn argument<'a>(terminators: &'static str) -> impl FnMut(&'a str) -> IResult<&'a str, String> {
+    delimited(
+        space0,
+        alt((
+            quoted('"'),
+            map(
+                verify(is_not(terminators), |s: &str| !s.trim().is_empty()),
+                |s: &str| s.trim().to_string(),
+            ),
+        )),
+        space0,
+    )
+}
+
+fn argument_pair(s: &str) -> IResult<&str, (String, String)> {
+    tuple((
+        argument(",)"),
+        map(
+            opt(preceded(char(','), argument(")"))),
+            Option::unwrap_or_default,
+        ),
+    ))(s)
+}
+
+/// Splits comma separated arguments entered without the surrounding condition, e.g. `"a, b", c`.
+pub fn arguments(s: &str) -> Vec<String> {
+    separated_list0(char(','), argument(","))(s)
+        .map(|(_, args)| args)
+        .unwrap_or_default()
+}
+
+/// Quotes an argument when it cannot be written as is.
+pub fn quote(s: &str) -> String {
+    if s.is_empty() || s.trim() != s || s.starts_with('"') || s.contains([')', ',']) {
+        quote_literal(s)
+    } else {
+        s.to_string()
+    }
+}
+
+/// Quotes an exact match when it would otherwise be parsed as something else.
+pub fn quote_exact(s: &str) -> String {
+    match any_condition(s) {
+        Ok(("", Ok(SearchCondition::Exact(e)))) if e == s && !s.contains(')') => s.to_string(),
+        _ => quote_literal(s),
+    }
+}
+
+fn quote_literal(s: &str) -> String {
+    let mut quoted = String::from('"');
+    let mut chars = s.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\\' if matches!(chars.peek(), None | Some('\\' | '"' | '\'' | 'n' | 't')) => {
+                quoted.push_str("\\\\")
+            }
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
 fn separator(s: &str) -> IResult<&str, &str> {
-    alt((eof, preceded(char('|'), rest)))(s.trim())
+    alt((
+        eof,
+        preceded(tuple((char('|'), not(char('|')))), rest),
+        preceded(peek(alt((tag(AND), tag(OR), tag(")")))), peek(rest)),
+    ))(s.trim())
+}
+
+fn exact_terminator(s: &str, in_group: bool) -> IResult<&str, &str> {
+    alt((tag("|"), tag(AND), verify(tag(")"), |_: &str| in_group)))(s)
 }
 
 fn is_valid_env_char(c: char) -> bool {
@@ -130,14 +389,18 @@ fn expr_env(s: &str) -> IResult<&str, Value> {
 }
 
 fn expr_num_or_string(s: &str) -> IResult<&str, Value> {
-    tuple((alt((digit1, string)), rest))(s).map(|(_, (v, s))| {
-        (
-            s.trim(),
-            v.parse()
-                .map(Value::Num)
-                .unwrap_or_else(|_| Value::Str(v.to_string())),
-        )
-    })
+    tuple((
+        alt((
+            map(quoted('"'), Value::Str),
+            map(alt((digit1, string)), |v| {
+                v.parse()
+                    .map(Value::Num)
+                    .unwrap_or_else(|_| Value::Str(v.to_string()))
+            }),
+        )),
+        rest,
+    ))(s)
+    .map(|(_, (v, s))| (s.trim(), v))
 }
 
 fn val(s: &str) -> IResult<&str, Value> {
@@ -171,40 +434,29 @@ fn ne(s: &str) -> IResult<&str, Value> {
 fn matcher_string(s: &str) -> IResult<&str, Result<SearchCondition>> {
     tuple((
         space0,
-        delimited(
-            tag("'"),
-            verify(is_not("'"), |s: &str| !s.is_empty()),
-            tag("'"),
-        ),
+        verify(alt((quoted('"'), quoted('\''))), |s: &str| !s.is_empty()),
         separator,
     ))(s)
-    .map(|(_, (_, matched, s))| {
-        (
-            s.trim(),
-            Ok(SearchCondition::Exact(matched.trim().to_string())),
-        )
-    })
+    .map(|(_, (_, matched, s))| (s.trim(), Ok(SearchCondition::Exact(matched))))
 }
 
 fn ignore_case(s: &str) -> IResult<&str, Result<SearchCondition>> {
     tuple((
         space0,
         tag(IGNORE_CASE),
-        delimited(char('('), string, char(')')),
+        delimited(char('('), argument(")"), char(')')),
         separator,
     ))(s)
-    .map(|(_, (_, _, matched, s))| {
-        (
-            s.trim(),
-            Ok(SearchCondition::IgnoreCase(matched.to_string())),
-        )
-    })
+    .map(|(_, (_, _, matched, s))| (s.trim(), Ok(SearchCondition::IgnoreCase(matched))))
 }
 
-fn exact(s: &str) -> IResult<&str, Result<SearchCondition>> {
+fn exact(s: &str, in_group: bool) -> IResult<&str, Result<SearchCondition>> {
     tuple((
         space0,
-        verify(is_not("|"), |s: &str| !s.is_empty()),
+        recognize(many1(preceded(
+            not(|s| exact_terminator(s, in_group)),
+            anychar,
+        ))),
         separator,
     ))(s)
     .map(|(_, (_, matched, s))| {
@@ -259,172 +511,157 @@ fn regex(s: &str) -> IResult<&str, Result<SearchCondition>> {
     tuple((
         space0,
         tag(REGEX),
-        delimited(char('('), string, char(')')),
+        delimited(char('('), argument(")"), char(')')),
         separator,
     ))(s)
-    .map(|(_, (_, _, matched, s))| (s.trim(), SearchCondition::new_regex(matched)))
+    .map(|(_, (_, _, matched, s))| (s.trim(), SearchCondition::new_regex(&matched)))
 }
 
 fn whole_word(s: &str) -> IResult<&str, Result<SearchCondition>> {
     tuple((
         space0,
         tag(WHOLE_WORD),
-        delimited(char('('), string, char(')')),
+        delimited(char('('), argument(")"), char(')')),
         separator,
     ))(s)
-    .map(|(_, (_, _, matched, s))| {
-        (
-            s.trim(),
-            Ok(SearchCondition::WholeWord(matched.to_string())),
-        )
-    })
+    .map(|(_, (_, _, matched, s))| (s.trim(), Ok(SearchCondition::WholeWord(matched))))
 }
 
 fn starts_with(s: &str) -> IResult<&str, Result<SearchCondition>> {
     tuple((
         space0,
         tag(STARTS_WITH),
-        delimited(char('('), string, char(')')),
+        delimited(char('('), argument(")"), char(')')),
         separator,
     ))(s)
-    .map(|(_, (_, _, matched, s))| {
-        (
-            s.trim(),
-            Ok(SearchCondition::StartsWith(matched.to_string())),
-        )
-    })
+    .map(|(_, (_, _, matched, s))| (s.trim(), Ok(SearchCondition::StartsWith(matched))))
 }
 
 fn line_starts_with(s: &str) -> IResult<&str, Result<SearchCondition>> {
     tuple((
         space0,
         tag(LINE_STARTS_WITH),
-        delimited(char('('), string, char(')')),
+        delimited(char('('), argument(")"), char(')')),
         separator,
     ))(s)
-    .map(|(_, (_, _, matched, s))| {
-        (
-            s.trim(),
-            Ok(SearchCondition::LineStartsWith(matched.to_string())),
-        )
-    })
+    .map(|(_, (_, _, matched, s))| (s.trim(), Ok(SearchCondition::LineStartsWith(matched))))
 }
 
 fn contains(s: &str) -> IResult<&str, Result<SearchCondition>> {
     tuple((
         space0,
         tag(CONTAINS),
-        delimited(char('('), string, char(')')),
+        delimited(char('('), argument(")"), char(')')),
         separator,
     ))(s)
-    .map(|(_, (_, _, matched, s))| (s.trim(), Ok(SearchCondition::Contains(matched.to_string()))))
+    .map(|(_, (_, _, matched, s))| (s.trim(), Ok(SearchCondition::Contains(matched))))
 }
 
 fn ends_with(s: &str) -> IResult<&str, Result<SearchCondition>> {
     tuple((
         space0,
         tag(ENDS_WITH),
-        delimited(char('('), string, char(')')),
+        delimited(char('('), argument(")"), char(')')),
         separator,
     ))(s)
-    .map(|(_, (_, _, matched, s))| (s.trim(), Ok(SearchCondition::EndsWith(matched.to_string()))))
+    .map(|(_, (_, _, matched, s))| (s.trim(), Ok(SearchCondition::EndsWith(matched))))
 }
 
 fn line_filter_ends_with(s: &str) -> IResult<&str, Result<SearchCondition>> {
     tuple((
         space0,
         tag(LINE_ENDS_WITH),
-        delimited(char('('), string, char(')')),
+        delimited(char('('), argument(")"), char(')')),
         separator,
     ))(s)
-    .map(|(_, (_, _, matched, s))| {
-        (
-            s.trim(),
-            Ok(SearchCondition::LineEndsWith(matched.to_string())),
-        )
-    })
+    .map(|(_, (_, _, matched, s))| (s.trim(), Ok(SearchCondition::LineEndsWith(matched))))
 }
 
 fn invert_match(s: &str) -> IResult<&str, Result<SearchCondition>> {
     tuple((
         space0,
         tag(INVERT_MATCH),
-        delimited(char('('), string, char(')')),
+        delimited(char('('), argument(")"), char(')')),
         separator,
     ))(s)
-    .map(|(_, (_, _, matched, s))| {
-        (
-            s.trim(),
-            Ok(SearchCondition::InvertMatch(matched.to_string())),
-        )
-    })
+    .map(|(_, (_, _, matched, s))| (s.trim(), Ok(SearchCondition::InvertMatch(matched))))
 }
 
 fn line_regex(s: &str) -> IResult<&str, Result<SearchCondition>> {
     tuple((
         space0,
         tag(LINE_REGEX),
-        delimited(char('('), string, char(')')),
+        delimited(char('('), argument(")"), char(')')),
         separator,
     ))(s)
-    .map(|(_, (_, _, matched, s))| (s.trim(), SearchCondition::new_line_match_regex(matched)))
+    .map(|(_, (_, _, matched, s))| (s.trim(), SearchCondition::new_line_match_regex(&matched)))
 }
 
 fn line_contains(s: &str) -> IResult<&str, Result<SearchCondition>> {
     tuple((
         space0,
         tag(LINE_CONTAINS),
-        delimited(char('('), string, char(')')),
+        delimited(char('('), argument(")"), char(')')),
         separator,
     ))(s)
-    .map(|(_, (_, _, matched, s))| {
-        (
-            s.trim(),
-            Ok(SearchCondition::LineContains(matched.to_string())),
-        )
-    })
+    .map(|(_, (_, _, matched, s))| (s.trim(), Ok(SearchCondition::LineContains(matched))))
 }
 
 fn line_invert_match(s: &str) -> IResult<&str, Result<SearchCondition>> {
     tuple((
         space0,
         tag(LINE_INVERT_MATCH),
-        delimited(char('('), string, char(')')),
+        delimited(char('('), argument(")"), char(')')),
         separator,
     ))(s)
-    .map(|(_, (_, _, matched, s))| {
-        (
-            s.trim(),
-            Ok(SearchCondition::LineInvertMatch(matched.to_string())),
-        )
-    })
+    .map(|(_, (_, _, matched, s))| (s.trim(), Ok(SearchCondition::LineInvertMatch(matched))))
 }
 
 fn invert_match_regex(s: &str) -> IResult<&str, Result<SearchCondition>> {
     tuple((
         space0,
         tag(INVERT_MATCH_REGEX),
-        delimited(char('('), string, char(')')),
+        delimited(char('('), argument(")"), char(')')),
         separator,
     ))(s)
-    .map(|(_, (_, _, matched, s))| (s.trim(), SearchCondition::new_invert_match_regex(matched)))
+    .map(|(_, (_, _, matched, s))| (s.trim(), SearchCondition::new_invert_match_regex(&matched)))
 }
 
 fn line_invert_match_regex(s: &str) -> IResult<&str, Result<SearchCondition>> {
     tuple((
         space0,
         tag(LINE_INVERT_MATCH_REGEX),
-        delimited(char('('), string, char(')')),
+        delimited(char('('), argument(")"), char(')')),
         separator,
     ))(s)
     .map(|(_, (_, _, matched, s))| {
         (
             s.trim(),
-            SearchCondition::new_line_invert_match_regex(matched),
+            SearchCondition::new_line_invert_match_regex(&matched),
         )
     })
 }
 
+fn file_ext(s: &str) -> IResult<&str, Result<SearchCondition>> {
+    tuple((
+        space0,
+        tag(FILE_EXT),
+        delimited(char('('), argument(")"), char(')')),
+        separator,
+    ))(s)
+    .map(|(_, (_, _, ext, s))| (s.trim(), Ok(SearchCondition::FileExt(ext))))
+}
+
+fn file_path(s: &str) -> IResult<&str, Result<SearchCondition>> {
+    tuple((
+        space0,
+        tag(FILE_PATH),
+        delimited(char('('), argument(")"), char(')')),
+        separator,
+    ))(s)
+    .map(|(_, (_, _, glob, s))| (s.trim(), SearchCondition::new_file_path(&glob)))
+}
+
 fn line_length_eq(s: &str) -> IResult<&str, Result<SearchCondition>> {
     tuple((space0, tag(LINE_LENGTH), tag("()"), eq, separator))(s)
         .map(|(_, (_, _, _, v, s))| (s.trim(), Ok(SearchCondition::LineLength(Some(Op::Eq(v))))))
@@ -525,17 +762,23 @@ fn replace(s: &str) -> IResult<&str, Result<SearchCondition>> {
     tuple((
         space0,
         tag(REPLACE),
-        delimited(char('('), string, char(')')),
+        delimited(char('('), argument_pair, char(')')),
         separator,
     ))(s)
-    .map(|(_, (_, _, matched, s))| {
-        let (from, to) = match matched.split(',').collect::<Vec<_>>().as_slice() {
-            [f, t] => (f.trim().to_string(), t.trim().to_string()),
-            _ => (matched.trim().to_string(), "".to_string()),
-        };
+    .map(|(_, (_, _, (from, to), s))| (s.trim(), Ok(SearchCondition::Replace(from, to))))
+}
+
+fn regex_replace(s: &str) -> IResult<&str, Result<SearchCondition>> {
+    tuple((
+        space0,
+        tag(REGEX_REPLACE),
+        delimited(char('('), argument_pair, char(')')),
+        separator,
+    ))(s)
+    .map(|(_, (_, _, (regex, template), s))| {
         (
             s.trim(),
-            Ok(SearchCondition::Replace(from.to_string(), to.to_string())),
+            SearchCondition::new_regex_replace(&regex, &template),
         )
     })
 }
@@ -544,10 +787,10 @@ fn update(s: &str) -> IResult<&str, Result<SearchCondition>> {
     tuple((
         space0,
         tag(UPDATE),
-        delimited(char('('), string, char(')')),
+        delimited(char('('), argument(")"), char(')')),
         separator,
     ))(s)
-    .map(|(_, (_, _, matched, s))| (s.trim(), Ok(SearchCondition::Update(matched.to_string()))))
+    .map(|(_, (_, _, matched, s))| (s.trim(), Ok(SearchCondition::Update(matched))))
 }
 
 fn insert(s: &str) -> IResult<&str, Result<SearchCondition>> {
@@ -558,18 +801,14 @@ fn insert(s: &str) -> IResult<&str, Result<SearchCondition>> {
         digit1,
         space0,
         tag(","),
-        space0,
-        string,
+        argument(")"),
         char(')'),
         separator,
     ))(s)
-    .map(|(_, (_, _, _, index, _, _, _, value, _, s))| {
+    .map(|(_, (_, _, _, index, _, _, value, _, s))| {
         (
             s.trim(),
-            Ok(SearchCondition::Insert(
-                index.parse().unwrap(),
-                value.to_string(),
-            )),
+            Ok(SearchCondition::Insert(index.parse().unwrap(), value)),
         )
     })
 }
@@ -724,6 +963,43 @@ fn upper_snake_case(s: &str) -> IResult<&str, Result<SearchCondition>> {
 #[cfg(test)]
 mod tests {
     use crate::parser::*;
+    use rstest::rstest;
+    use std::collections::BTreeMap;
+
+    fn parse(s: &str) -> Result<Vec<SearchCondition>> {
+        super::parse(s, &Aliases::default())
+    }
+
+    #[rstest]
+    #[case(
+        "number() >= 10",
+        SearchCondition::Number(Some(Op::Gte(Value::Num(10))))
+    )]
+    #[case("number() > 10", SearchCondition::Number(Some(Op::Gt(Value::Num(10)))))]
+    #[case(
+        "number() <= 10",
+        SearchCondition::Number(Some(Op::Lte(Value::Num(10))))
+    )]
+    #[case("number() < 10", SearchCondition::Number(Some(Op::Lt(Value::Num(10)))))]
+    #[case(
+        "line.length() >= 10",
+        SearchCondition::LineLength(Some(Op::Gte(Value::Num(10))))
+    )]
+    #[case(
+        "line.length() > 10",
+        SearchCondition::LineLength(Some(Op::Gt(Value::Num(10))))
+    )]
+    #[case(
+        "line.length() <= 10",
+        SearchCondition::LineLength(Some(Op::Lte(Value::Num(10))))
+    )]
+    #[case(
+        "line.length() < 10",
+        SearchCondition::LineLength(Some(Op::Lt(Value::Num(10))))
+    )]
+    fn test_comparison_parser(#[case] s: &str, #[case] expected: SearchCondition) {
+        assert_eq!(parse(s).unwrap(), vec![expected]);
+    }
 
     #[test]
     fn test_parser() {
@@ -758,6 +1034,8 @@ mod tests {
                  line.ends_with(test) | \
                  line.invert_match(test) | \
                  line.invert_match_regex(test) | \
+                 file.ext(rs) | \
+                 file.path(src/**) | \
                  contains(test) | \
                  ignore_case(test) | \
                  regex(test) | \
@@ -767,6 +1045,7 @@ mod tests {
                  invert_match(test) | \
                  invert_match_regex(test) | \
                  replace(from, to) | \
+                 regex_replace(fo+, $0$0) | \
                  camel_case() | \
                  kebab_case() | \
                  snake_case() | \
@@ -815,6 +1094,8 @@ mod tests {
                 SearchCondition::LineEndsWith("test".to_string()),
                 SearchCondition::LineInvertMatch("test".to_string()),
                 SearchCondition::LineInvertMatchRegex("test".to_string()),
+                SearchCondition::FileExt("rs".to_string()),
+                SearchCondition::FilePath("src/**".to_string()),
                 SearchCondition::Contains("test".to_string()),
                 SearchCondition::IgnoreCase("test".to_string()),
                 SearchCondition::Regex("test".to_string()),
@@ -824,6 +1105,7 @@ mod tests {
                 SearchCondition::InvertMatch("test".to_string()),
                 SearchCondition::InvertMatchRegex("test".to_string()),
                 SearchCondition::Replace("from".to_string(), "to".to_string()),
+                SearchCondition::RegexReplace("fo+".to_string(), "$0$0".to_string()),
                 SearchCondition::CamelCase,
                 SearchCondition::KebabCase,
                 SearchCondition::SnakeCase,
@@ -843,4 +1125,212 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_boolean_parser() {
+        let exact = |s: &str| Box::new(SearchCondition::Exact(s.to_string()));
+
+        assert_eq!(
+            parse("foo || bar && !baz | upper_case()").unwrap(),
+            vec![
+                SearchCondition::Or(
+                    exact("foo"),
+                    Box::new(SearchCondition::And(
+                        exact("bar"),
+                        Box::new(SearchCondition::Not(exact("baz")))
+                    ))
+                ),
+                SearchCondition::UpperCase,
+            ]
+        );
+        assert_eq!(
+            parse("('foo()' || regex(ba+r)) && !(line.contains(test) || line.length() > 10)")
+                .unwrap(),
+            vec![SearchCondition::And(
+                Box::new(SearchCondition::Or(
+                    exact("foo()"),
+                    Box::new(SearchCondition::Regex("ba+r".to_string()))
+                )),
+                Box::new(SearchCondition::Not(Box::new(SearchCondition::Or(
+                    Box::new(SearchCondition::LineContains("test".to_string())),
+                    Box::new(SearchCondition::LineLength(Some(Op::Gt(Value::Num(10)))))
+                ))))
+            )]
+        );
+        assert_eq!(
+            parse("foo() | 'a && b'").unwrap(),
+            vec![
+                SearchCondition::Exact("foo()".to_string()),
+                SearchCondition::Exact("a && b".to_string()),
+            ]
+        );
+        assert!(parse("foo && upper_case()").is_err());
+    }
+
+    #[test]
+    fn test_boolean_round_trip() {
+        let conditions = parse("(foo || bar) && !line.contains(test)").unwrap();
+        assert_eq!(
+            parse(&conditions.first().unwrap().to_string()).unwrap(),
+            conditions
+        );
+    }
+
+    #[test]
+    fn test_quoted_parser() {
+        assert_eq!(
+            parse(
+                r#""foo()" | 'it\'s' | replace(a, "a, b") | regex("foo\((\w+)\)") | contains(" | ") | update("say \"hi\"\n") | insert(0, ")")"#
+            )
+            .unwrap(),
+            vec![
+                SearchCondition::Exact("foo()".to_string()),
+                SearchCondition::Exact("it's".to_string()),
+                SearchCondition::Replace("a".to_string(), "a, b".to_string()),
+                SearchCondition::Regex(r"foo\((\w+)\)".to_string()),
+                SearchCondition::Contains(" | ".to_string()),
+                SearchCondition::Update("say \"hi\"\n".to_string()),
+                SearchCondition::Insert(0, ")".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_quoted_round_trip() {
+        let conditions = vec![
+            SearchCondition::Exact("foo()".to_string()),
+            SearchCondition::Exact("a | b".to_string()),
+            SearchCondition::Exact("ignore_case(test)".to_string()),
+            SearchCondition::Exact(" \"quoted\" ".to_string()),
+            SearchCondition::IgnoreCase("a)b".to_string()),
+            SearchCondition::Regex(r"foo\((\w+)\)\\".to_string()),
+            SearchCondition::Replace("a, b".to_string(), "".to_string()),
+            SearchCondition::RegexReplace(r"\s+".to_string(), "\t".to_string()),
+            SearchCondition::Insert(1, " ".to_string()),
+            SearchCondition::Number(Some(Op::Eq(Value::Str("a)".to_string())))),
+            SearchCondition::Or(
+                Box::new(SearchCondition::Exact("(x)".to_string())),
+                Box::new(SearchCondition::Exact("y && z".to_string())),
+            ),
+        ];
+
+        for condition in conditions {
+            assert_eq!(
+                parse(&condition.to_string()).unwrap(),
+                vec![condition.clone()],
+                "{}",
+                condition
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = |s: &str| {
+            parse(s)
+                .unwrap_err()
+                .downcast::<ParseError>()
+                .unwrap()
+                .to_string()
+        };
+
+        assert_eq!(
+            error("foo | ignorecase(bar)"),
+            "unknown condition at byte 6: `ignorecase(bar)`, did you mean `ignore_case`?"
+        );
+        assert_eq!(
+            error("regex(foo"),
+            "invalid arguments at byte 0: `regex(foo`, expected `regex(string)`"
+        );
+        assert_eq!(
+            error("foo | | bar"),
+            "missing condition at byte 6: `|`, expected a condition before `|`"
+        );
+        assert_eq!(
+            error("foo && | bar"),
+            "missing condition at byte 4: `&&`, expected a condition after `&&`"
+        );
+        assert_eq!(
+            error("(foo || bar"),
+            "unclosed parenthesis at byte 0: `(foo`, expected `)`"
+        );
+        assert_eq!(
+            error("foo | regex([)"),
+            "unclosed character class at byte 6: `regex([)`"
+        );
+        assert_eq!(
+            error("file.path(src/{a) | foo"),
+            "error parsing glob 'src/{a': unclosed alternate group; missing '}' (maybe escape '{' with '[{]'?) at byte 0: `file.path(src/{a)`"
+        );
+        assert_eq!(
+            error("foo | \"unterminated"),
+            "unterminated string literal starting at byte 6: `\"unterminated`"
+        );
+        assert_eq!(
+            error("'unterminated"),
+            "unterminated string literal starting at byte 0: `'unterminated`"
+        );
+        assert!(parse("foo() | \"ignorecase(bar)\"").is_ok());
+    }
+
+    #[test]
+    fn test_alias_parser() {
+        let aliases = Aliases::new(
+            BTreeMap::from([("deprecated".to_string(), "todo(deprecated)".to_string())]),
+            BTreeMap::from([
+                (
+                    "todo".to_string(),
+                    "line.regex(TODO|FIXME) | whole_word({1})".to_string(),
+                ),
+                ("loop".to_string(), "loop()".to_string()),
+                (
+                    "ren".to_string(),
+                    "regex(\"fo(o)\") | update(\"bar($1)\")".to_string(),
+                ),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            super::parse("@deprecated | upper_case()", &aliases).unwrap(),
+            vec![
+                SearchCondition::LineRegex("TODO|FIXME".to_string()),
+                SearchCondition::WholeWord("deprecated".to_string()),
+                SearchCondition::UpperCase,
+            ]
+        );
+        assert_eq!(
+            super::parse("foo | loop()", &aliases)
+                .unwrap_err()
+                .downcast::<ParseError>()
+                .unwrap()
+                .message,
+            "invalid alias (alias expands recursively at byte 0: `loop()`)"
+        );
+        assert_eq!(
+            super::parse("todo()", &aliases).unwrap_err().to_string(),
+            "alias `todo` expects 1 arguments, got 0 at byte 0: `todo()`"
+        );
+        assert_eq!(
+            super::parse("ren()", &aliases).unwrap(),
+            vec![
+                SearchCondition::Regex("fo(o)".to_string()),
+                SearchCondition::Update("bar($1)".to_string()),
+            ]
+        );
+        assert_eq!(
+            super::parse("foo | @deprecate", &aliases)
+                .unwrap_err()
+                .to_string(),
+            "unknown saved query at byte 6: `@deprecate`, did you mean `@deprecated`?"
+        );
+        assert_eq!(
+            super::parse("@nope", &aliases).unwrap_err().to_string(),
+            "unknown saved query at byte 0: `@nope`"
+        );
+        assert_eq!(
+            super::parse("'@nope'", &aliases).unwrap(),
+            vec![SearchCondition::Exact("@nope".to_string())]
+        );
+    }
 }
@@ -0,0 +1,201 @@
+use super::{argument, diagnostic, env_name};
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use nom::branch::alt;
+use nom::character::complete::{char, space0};
+use nom::combinator::{eof, not, rest};
+use nom::multi::separated_list0;
+use nom::sequence::{delimited, preceded, tuple};
+use nom::IResult;
+use regex::{Captures, Regex};
+use std::collections::BTreeMap;
+
+/// Saved queries invoked as `@name` and condition aliases invoked as `name(args)`,
+/// which expand to the stages they stand for. `{1}`, `{2}`, ... in an alias are replaced by its arguments,
+/// and `{{1}}` is kept as `{1}`, so that `$1` capture references and regex repetitions can be used in aliases.
+#[derive(Clone, Debug, Default)]
+pub struct Aliases {
+    queries: BTreeMap<String, String>,
+    aliases: BTreeMap<String, String>,
+}
+
+impl Aliases {
+    pub fn new(
+        queries: BTreeMap<String, String>,
+        aliases: BTreeMap<String, String>,
+    ) -> Result<Self> {
+        if let Some(name) = queries.keys().chain(aliases.keys()).find(|name| {
+            name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }) {
+            return Err(anyhow!("Invalid alias name: `{}`", name));
+        }
+
+        if let Some(name) = aliases.keys().find(|name| diagnostic::is_condition(name)) {
+            return Err(anyhow!("Alias `{}` shadows a built-in condition", name));
+        }
+
+        Ok(Self { queries, aliases })
+    }
+
+    pub fn queries(&self) -> &BTreeMap<String, String> {
+        &self.queries
+    }
+
+    /// Returns whether the whole input is a single saved query or alias invocation.
+    pub fn is_invocation(&self, s: &str) -> bool {
+        matches!(self.invocation(s), Some((rest, _)) if rest.trim().is_empty())
+    }
+
+    /// Returns the name of a saved query invoked at the start of `s` that is not defined.
+    pub fn unknown_query<'a>(&self, s: &'a str) -> Option<&'a str> {
+        saved_query(s)
+            .ok()
+            .map(|(_, name)| name)
+            .filter(|name| !self.queries.contains_key(*name))
+    }
+
+    /// Parses a saved query or alias invocation at the start of `s`,
+    /// returning the rest of the input and the expanded query.
+    pub fn invocation<'a>(&self, s: &'a str) -> Option<(&'a str, Result<String>)> {
+        saved_query(s)
+            .ok()
+            .and_then(|(rest, name)| {
+                self.queries
+                    .get(name)
+                    .map(|query| (rest, Ok(query.clone())))
+            })
+            .or_else(|| {
+                alias_call(s).ok().and_then(|(rest, (name, args))| {
+                    self.aliases
+                        .get(name)
+                        .map(|alias| (rest, Self::expand(name, alias, &args)))
+                })
+            })
+    }
+
+    fn expand(name: &str, alias: &str, args: &[String]) -> Result<String> {
+        let re = Regex::new(r"\{(\{[1-9]\})\}|\{([1-9])\}").unwrap();
+        let params = re
+            .captures_iter(alias)
+            .filter_map(|c| c.get(2)?.as_str().parse::<usize>().ok())
+            .max()
+            .unwrap_or_default();
+
+        if params != args.len() {
+            return Err(anyhow!(
+                "alias `{}` expects {} arguments, got {}",
+                name,
+                params,
+                args.len()
+            ));
+        }
+
+        Ok(re
+            .replace_all(alias, |c: &Captures| match c.get(2) {
+                Some(i) => i
+                    .as_str()
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|i| args.get(i - 1))
+                    .map(|arg| super::quote(arg))
+                    .unwrap_or_default(),
+                None => c[1].to_string(),
+            })
+            .to_string())
+    }
+}
+
+/// The end of an invocation, which has to be a whole stage.
+fn pipe(s: &str) -> IResult<&str, &str> {
+    alt((eof, preceded(tuple((char('|'), not(char('|')))), rest)))(s.trim())
+}
+
+fn saved_query(s: &str) -> IResult<&str, &str> {
+    tuple((space0, preceded(char('@'), env_name), pipe))(s)
+        .map(|(_, (_, name, rest))| (rest.trim(), name))
+}
+
+fn alias_call(s: &str) -> IResult<&str, (&str, Vec<String>)> {
+    tuple((
+        space0,
+        env_name,
+        delimited(
+            char('('),
+            separated_list0(char(','), argument(",)")),
+            preceded(space0, char(')')),
+        ),
+        pipe,
+    ))(s)
+    .map(|(_, (_, name, args, rest))| (rest.trim(), (name, args.into_iter().collect_vec())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn aliases() -> Aliases {
+        Aliases::new(
+            BTreeMap::from([(
+                "deprecated".to_string(),
+                "deprecated | snake_case()".to_string(),
+            )]),
+            BTreeMap::from([
+                (
+                    "todo".to_string(),
+                    "line.regex(TODO|FIXME) | whole_word({1})".to_string(),
+                ),
+                ("swap".to_string(), "replace({1}, {2})".to_string()),
+                (
+                    "year".to_string(),
+                    "regex(({1})-([0-9]{{4}})) | update(\"$2-$1\")".to_string(),
+                ),
+            ]),
+        )
+        .unwrap()
+    }
+
+    #[rstest]
+    #[case("@deprecated", Some(("", "deprecated | snake_case()")))]
+    #[case("@deprecated | upper_case()", Some(("upper_case()", "deprecated | snake_case()")))]
+    #[case("todo(foo) | upper_case()", Some(("upper_case()", "line.regex(TODO|FIXME) | whole_word(foo)")))]
+    #[case("swap(\"a, b\", c)", Some(("", "replace(\"a, b\", c)")))]
+    #[case("year(Q1)", Some(("", "regex((Q1)-([0-9]{4})) | update(\"$2-$1\")")))]
+    #[case("@unknown", None)]
+    #[case("foo(bar)", None)]
+    #[case("todo(foo) bar", None)]
+    fn test_invocation(#[case] s: &str, #[case] expected: Option<(&str, &str)>) {
+        assert_eq!(
+            aliases()
+                .invocation(s)
+                .map(|(rest, query)| (rest, query.unwrap())),
+            expected.map(|(rest, query)| (rest, query.to_string()))
+        );
+    }
+
+    #[rstest]
+    #[case("todo()", "alias `todo` expects 1 arguments, got 0")]
+    #[case("swap(a)", "alias `swap` expects 2 arguments, got 1")]
+    #[case("year()", "alias `year` expects 1 arguments, got 0")]
+    fn test_invocation_error(#[case] s: &str, #[case] expected: &str) {
+        assert_eq!(
+            aliases().invocation(s).unwrap().1.unwrap_err().to_string(),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case("regex", "Alias `regex` shadows a built-in condition")]
+    #[case("to-do", "Invalid alias name: `to-do`")]
+    fn test_new_error(#[case] name: &str, #[case] expected: &str) {
+        assert_eq!(
+            Aliases::new(
+                BTreeMap::new(),
+                BTreeMap::from([(name.to_string(), "foo".to_string())])
+            )
+            .unwrap_err()
+            .to_string(),
+            expected
+        );
+    }
+}
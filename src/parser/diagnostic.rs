@@ -0,0 +1,330 @@
+use crate::models::search_condition::{
+    AND, CAMEL_CASE, CONSTANT, CONTAINS, DELETE, ENDS_WITH, FILE_EXT, FILE_PATH, IGNORE_CASE,
+    INSERT, INVERT_MATCH, INVERT_MATCH_REGEX, KEBAB_CASE, LINE_BYTE_LENGTH, LINE_CONTAINS,
+    LINE_ENDS_WITH, LINE_INVERT_MATCH, LINE_INVERT_MATCH_REGEX, LINE_LENGTH, LINE_REGEX,
+    LINE_STARTS_WITH, LOWER_CASE, NUMBER, OR, REGEX, REGEX_REPLACE, REPLACE, SNAKE_CASE,
+    STARTS_WITH, TRIM, TRIM_END, TRIM_START, UPDATE, UPPER_CAMEL_CASE, UPPER_CASE,
+    UPPER_KEBAB_CASE, UPPER_SNAKE_CASE, WHOLE_WORD,
+};
+use crate::models::SearchCondition;
+use itertools::Itertools;
+use std::fmt::{self, Display, Formatter};
+
+const USAGES: &[(&str, &str)] = &[
+    (NUMBER, "number() == value"),
+    (CONTAINS, "contains(string)"),
+    (CONSTANT, "constant()"),
+    (IGNORE_CASE, "ignore_case(string)"),
+    (WHOLE_WORD, "whole_word(string)"),
+    (REGEX, "regex(string)"),
+    (STARTS_WITH, "starts_with(string)"),
+    (ENDS_WITH, "ends_with(string)"),
+    (INVERT_MATCH, "invert_match(string)"),
+    (INVERT_MATCH_REGEX, "invert_match_regex(string)"),
+    (LINE_REGEX, "line.regex(string)"),
+    (LINE_STARTS_WITH, "line.starts_with(string)"),
+    (LINE_ENDS_WITH, "line.ends_with(string)"),
+    (LINE_INVERT_MATCH, "line.invert_match(string)"),
+    (LINE_INVERT_MATCH_REGEX, "line.invert_match_regex(string)"),
+    (LINE_LENGTH, "line.length() == value"),
+    (LINE_BYTE_LENGTH, "line.bytelength() == value"),
+    (LINE_CONTAINS, "line.contains(string)"),
+    (FILE_EXT, "file.ext(extension)"),
+    (FILE_PATH, "file.path(glob)"),
+    (REPLACE, "replace(string, string)"),
+    (REGEX_REPLACE, "regex_replace(string, string)"),
+    (INSERT, "insert(index, string)"),
+    (DELETE, "delete(index, index)"),
+    (CAMEL_CASE, "camel_case()"),
+    (KEBAB_CASE, "kebab_case()"),
+    (SNAKE_CASE, "snake_case()"),
+    (TRIM_END, "trim_end()"),
+    (TRIM_START, "trim_start()"),
+    (TRIM, "trim()"),
+    (UPDATE, "update(string)"),
+    (UPPER_CASE, "upper_case()"),
+    (LOWER_CASE, "lower_case()"),
+    (UPPER_CAMEL_CASE, "upper_camel_case()"),
+    (UPPER_KEBAB_CASE, "upper_kebab_case()"),
+    (UPPER_SNAKE_CASE, "upper_snake_case()"),
+];
+
+/// A query that could not be parsed, pointing at the byte offset of the failing condition.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub offset: usize,
+    pub condition: String,
+    pub message: String,
+    pub expected: Option<String>,
+    pub suggestion: Option<String>,
+}
+
+impl ParseError {
+    pub fn new(offset: usize, condition: &str, message: &str) -> Self {
+        Self {
+            offset,
+            condition: condition.to_string(),
+            message: message.to_string(),
+            expected: None,
+            suggestion: None,
+        }
+    }
+
+    pub fn expected(self, expected: &str) -> Self {
+        Self {
+            expected: Some(expected.to_string()),
+            ..self
+        }
+    }
+
+    pub fn suggestion(self, suggestion: &str) -> Self {
+        Self {
+            suggestion: Some(suggestion.to_string()),
+            ..self
+        }
+    }
+
+    /// Renders the error followed by the query with a caret under the failing condition.
+    pub fn report(&self, query: &str) -> String {
+        let column = query
+            .get(..self.offset)
+            .map(|s| s.chars().count())
+            .unwrap_or_default();
+
+        format!("{}\n  {}\n  {}^", self, query, " ".repeat(column))
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} at byte {}: `{}`",
+            self.message, self.offset, self.condition
+        )?;
+
+        if let Some(expected) = &self.expected {
+            write!(f, ", expected {}", expected)?;
+        }
+
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, ", did you mean `{}`?", suggestion)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Diagnoses input that no condition parser accepted.
+pub fn unexpected(query: &str, input: &str) -> ParseError {
+    let offset = offset(query, input);
+    let input = input.trim_start();
+
+    match [AND, OR, "|"].into_iter().find(|op| input.starts_with(op)) {
+        Some(op) => {
+            let rest = input[op.len()..].trim_start();
+            let position = if rest.is_empty() || rest.starts_with('|') {
+                "after"
+            } else {
+                "before"
+            };
+
+            ParseError::new(offset, op, "missing condition")
+                .expected(&format!("a condition {} `{}`", position, op))
+        }
+        None => ParseError::new(offset, input, "invalid condition").expected("a condition"),
+    }
+}
+
+/// Diagnoses a condition whose arguments were rejected, e.g. an invalid regex.
+pub fn invalid(query: &str, input: &str, stage: &str, error: anyhow::Error) -> ParseError {
+    let message = error.to_string();
+    let message = message
+        .lines()
+        .rfind(|l| !l.trim().is_empty())
+        .unwrap_or_default()
+        .trim()
+        .trim_start_matches("error: ");
+
+    ParseError::new(
+        offset(query, input) + input.len() - input.trim_start().len(),
+        stage,
+        message,
+    )
+}
+
+/// Finds exact matches that were most likely meant as conditions, such as `ignorecase(foo)` or `regex(foo`.
+pub fn verify(
+    query: &str,
+    stage: &str,
+    condition: &SearchCondition,
+    is_operand: bool,
+) -> Result<(), ParseError> {
+    match condition {
+        SearchCondition::And(l, r) | SearchCondition::Or(l, r) => {
+            verify(query, stage, l, true)?;
+            verify(query, stage, r, true)
+        }
+        SearchCondition::Not(c) => verify(query, stage, c, true),
+        SearchCondition::Exact(s) => match unquoted_offset(stage, s) {
+            Some(i) => verify_exact(offset(query, stage) + i, s, is_operand),
+            None => Ok(()),
+        },
+        _ => Ok(()),
+    }
+}
+
+fn verify_exact(offset: usize, s: &str, is_operand: bool) -> Result<(), ParseError> {
+    if is_unterminated(s) {
+        return Err(ParseError::new(
+            offset,
+            s,
+            "unterminated string literal starting",
+        ));
+    }
+
+    if is_operand && s.starts_with('(') {
+        return Err(ParseError::new(offset, s, "unclosed parenthesis").expected("`)`"));
+    }
+
+    let Some((name, _)) = s.split_once('(') else {
+        return Ok(());
+    };
+
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+    {
+        return Ok(());
+    }
+
+    match USAGES.iter().find(|(n, _)| *n == name) {
+        Some((_, usage)) => {
+            Err(ParseError::new(offset, s, "invalid arguments").expected(&format!("`{}`", usage)))
+        }
+        None => match suggest(name) {
+            Some(suggestion) => {
+                Err(ParseError::new(offset, s, "unknown condition").suggestion(suggestion))
+            }
+            None => Ok(()),
+        },
+    }
+}
+
+/// Returns whether the text opens a string literal without closing it, e.g. `"foo`.
+fn is_unterminated(s: &str) -> bool {
+    let mut chars = s.chars();
+    let Some(quote) = chars.next().filter(|c| matches!(c, '"' | '\'')) else {
+        return false;
+    };
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            c if c == quote => return false,
+            _ => (),
+        }
+    }
+
+    true
+}
+
+pub fn is_condition(name: &str) -> bool {
+    USAGES.iter().any(|(n, _)| *n == name)
+}
+
+/// Diagnoses a saved query that is not defined, suggesting the closest one that is.
+pub fn unknown_query<'a>(
+    query: &str,
+    input: &str,
+    name: &str,
+    names: impl Iterator<Item = &'a str>,
+) -> ParseError {
+    let error = ParseError::new(
+        offset(query, input) + input.len() - input.trim_start().len(),
+        &format!("@{}", name),
+        "unknown saved query",
+    );
+
+    match closest(name, names) {
+        Some(suggestion) => error.suggestion(&format!("@{}", suggestion)),
+        None => error,
+    }
+}
+
+/// Returns the closest condition name, if it is close enough to be a typo.
+pub fn suggest(name: &str) -> Option<&'static str> {
+    closest(name, USAGES.iter().map(|(n, _)| *n))
+}
+
+fn closest<'a>(name: &str, names: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    names
+        .map(|n| (n, distance(name, n)))
+        .filter(|(n, d)| *d > 0 && *d <= 2 && d * 3 <= n.len())
+        .min_by_key(|(_, d)| *d)
+        .map(|(n, _)| n)
+}
+
+fn distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect_vec();
+    let mut row = (0..=b.len()).collect_vec();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                prev
+            } else {
+                prev.min(row[j]).min(row[j + 1]) + 1
+            };
+            prev = current;
+        }
+    }
+
+    row[b.len()]
+}
+
+fn offset(query: &str, input: &str) -> usize {
+    input.as_ptr() as usize - query.as_ptr() as usize
+}
+
+fn unquoted_offset(stage: &str, s: &str) -> Option<usize> {
+    stage
+        .match_indices(s)
+        .map(|(i, _)| i)
+        .find(|i| !stage[..*i].ends_with(['"', '\'']))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("ignorecase", Some(IGNORE_CASE))]
+    #[case("line.contain", Some(LINE_CONTAINS))]
+    #[case("uppercase", Some(UPPER_CASE))]
+    #[case("regex", None)]
+    #[case("foo", None)]
+    #[case("tri", Some(TRIM))]
+    fn test_suggest(#[case] name: &str, #[case] expected: Option<&str>) {
+        assert_eq!(suggest(name), expected);
+    }
+
+    #[test]
+    fn test_report() {
+        let error =
+            ParseError::new(6, "ignorecase(foo)", "unknown condition").suggestion(IGNORE_CASE);
+        assert_eq!(
+            error.report("foo | ignorecase(foo)"),
+            "unknown condition at byte 6: `ignorecase(foo)`, did you mean `ignore_case`?\n  foo | ignorecase(foo)\n        ^"
+        );
+    }
+}
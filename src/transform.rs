@@ -1,3 +1,4 @@
+use crate::models::Captures;
 use std::fmt::{Debug, Display};
 use std::ops::Range;
 
@@ -6,6 +7,7 @@ mod delete;
 mod insert;
 mod kebab_case;
 mod lower_case;
+mod regex_replace;
 mod replace;
 mod snake_case;
 mod trim;
@@ -22,6 +24,7 @@ pub use self::delete::Delete;
 pub use self::insert::Insert;
 pub use self::kebab_case::KebabCase;
 pub use self::lower_case::LowerCase;
+pub use self::regex_replace::RegexReplace;
 pub use self::replace::Replace;
 pub use self::snake_case::SnakeCase;
 pub use self::trim::Trim;
@@ -35,6 +38,10 @@ pub use self::upper_snake_case::UpperSnakeCase;
 
 pub trait Transform: Debug + Display + Send + Sync + 'static {
     fn transform(&self, text: &str) -> Vec<(String, Range<usize>)>;
+
+    fn transform_captures(&self, text: &str, _captures: &Captures) -> Vec<(String, Range<usize>)> {
+        self.transform(text)
+    }
 }
 
 impl PartialEq for dyn Transform {
@@ -0,0 +1,71 @@
+use crate::models::search_condition::REGEX_REPLACE;
+
+use super::Transform;
+use anyhow::Result;
+use std::fmt::{Debug, Display};
+use std::ops::Range;
+use std::sync::Arc;
+
+pub struct RegexReplace {
+    re: regex::Regex,
+    template: String,
+}
+
+impl RegexReplace {
+    pub fn new(regex: String, template: String) -> Result<Arc<Self>> {
+        let re = regex::Regex::new(&regex)?;
+        Ok(Arc::new(RegexReplace { re, template }))
+    }
+}
+
+impl Transform for RegexReplace {
+    fn transform(&self, text: &str) -> Vec<(String, Range<usize>)> {
+        vec![(
+            self.re
+                .replace_all(text, self.template.as_str())
+                .to_string(),
+            Range {
+                start: 0,
+                end: text.len(),
+            },
+        )]
+    }
+}
+
+impl Debug for RegexReplace {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl Display for RegexReplace {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}({}, {})", REGEX_REPLACE, self.re, self.template)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("foo(value)", r"foo\((\w+)\)", "bar($1)", vec![("bar(value)".to_string(), Range{start: 0, end: 10})])]
+    #[case("key=10", r"(?<k>\w+)=(?<v>\d+)", "${v}=${k}", vec![("10=key".to_string(), Range{start: 0, end: 6})])]
+    #[case("test_test", "t(e)", "$1", vec![("est_est".to_string(), Range{start: 0, end: 9})])]
+    #[should_panic]
+    #[case("test", "++", "", Vec::new())]
+    fn test(
+        #[case] text: String,
+        #[case] regex: String,
+        #[case] template: String,
+        #[case] expected: Vec<(String, Range<usize>)>,
+    ) {
+        assert_eq!(
+            RegexReplace::new(regex, template)
+                .unwrap()
+                .transform(text.as_str()),
+            expected
+        )
+    }
+}
@@ -1,6 +1,7 @@
 use crate::models::search_condition::REPLACE;
 
 use super::Transform;
+use crate::models::Captures;
 use std::fmt::{Debug, Display};
 use std::ops::Range;
 use std::sync::Arc;
@@ -26,6 +27,16 @@ impl Transform for Replace {
             },
         )]
     }
+
+    fn transform_captures(&self, text: &str, captures: &Captures) -> Vec<(String, Range<usize>)> {
+        vec![(
+            text.replace(&self.from, &captures.expand(&self.to)),
+            Range {
+                start: 0,
+                end: text.len(),
+            },
+        )]
+    }
 }
 
 impl Debug for Replace {
@@ -56,4 +67,16 @@ mod tests {
     ) {
         assert_eq!(Replace::new(from, to).transform(text.as_str()), expected)
     }
+
+    #[test]
+    fn test_transform_captures() {
+        let re = regex::Regex::new(r"foo\((\w+)\)").unwrap();
+        let captures = Captures::new(&re, &re.captures("foo(bar)").unwrap());
+
+        assert_eq!(
+            Replace::new("foo".to_string(), "baz_$1".to_string())
+                .transform_captures("foo(bar)", &captures),
+            vec![("baz_bar(bar)".to_string(), Range { start: 0, end: 8 })]
+        )
+    }
 }
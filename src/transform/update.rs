@@ -1,4 +1,5 @@
 use super::Transform;
+use crate::models::Captures;
 use std::fmt::{Debug, Display};
 use std::ops::Range;
 use std::sync::Arc;
@@ -23,6 +24,16 @@ impl Transform for Update {
             },
         )]
     }
+
+    fn transform_captures(&self, text: &str, captures: &Captures) -> Vec<(String, Range<usize>)> {
+        vec![(
+            captures.expand(&self.value),
+            Range {
+                start: 0,
+                end: text.len(),
+            },
+        )]
+    }
 }
 
 impl Debug for Update {
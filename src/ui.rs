@@ -1,4 +1,5 @@
 mod confirm;
+mod diff_preview;
 mod event;
 mod file_preview;
 mod help;
@@ -9,10 +10,13 @@ mod match_colors;
 mod search_condition;
 mod search_result;
 mod select_contidion;
+mod select_history;
 mod status;
+pub mod syntax;
 mod theme;
 
 pub use self::confirm::Confirm;
+pub use self::diff_preview::DiffPreview;
 pub use self::event::Event;
 pub use self::file_preview::FilePreview;
 pub use self::help::Help;
@@ -25,8 +29,11 @@ pub use self::match_colors::MatchColors;
 pub use self::search_condition::SearchCondition;
 pub use self::search_result::SearchResult;
 pub use self::select_contidion::SelectCondition;
+pub use self::select_history::SelectHistory;
 pub use self::status::Message;
 pub use self::status::Status;
+pub use self::theme::ColorSupport;
+pub use self::theme::CustomTheme;
 pub use self::theme::Dark;
 pub use self::theme::Light;
 pub use self::theme::Theme;
@@ -126,7 +126,7 @@ impl Confirm {
         }
     }
 
-    fn button(text: &str, selected: bool, theme: Arc<dyn Theme>) -> Paragraph {
+    fn button(text: &str, selected: bool, theme: Arc<dyn Theme>) -> Paragraph<'_> {
         Paragraph::new(format!(" {} ", text)).style(if selected {
             theme.selected_button_style()
         } else {
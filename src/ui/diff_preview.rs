@@ -0,0 +1,99 @@
+use super::Theme;
+use crate::ui;
+use anyhow::Result;
+use itertools::Itertools;
+use ratatui::crossterm::event::{Event, KeyCode, KeyEvent};
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    text::Line,
+    widgets::{Block, Clear, Paragraph},
+    Frame,
+};
+use std::sync::{mpsc, Arc};
+
+#[derive(Clone, Debug)]
+pub struct DiffPreview {
+    lines: Vec<String>,
+    offset: usize,
+    height: usize,
+    tx: mpsc::Sender<ui::Event>,
+}
+
+impl DiffPreview {
+    pub fn new(diff: String, tx: mpsc::Sender<ui::Event>) -> Self {
+        DiffPreview {
+            lines: if diff.is_empty() {
+                vec!["No changes".to_string()]
+            } else {
+                diff.lines().map(|l| l.to_string()).collect_vec()
+            },
+            offset: 0,
+            height: 0,
+            tx,
+        }
+    }
+
+    pub fn draw(&mut self, f: &mut Frame, area: Rect, theme: Arc<dyn Theme>) {
+        self.height = area.height.saturating_sub(2) as usize;
+
+        let lines = self
+            .lines
+            .iter()
+            .skip(self.offset)
+            .take(self.height)
+            .map(|line| Line::styled(line.as_str(), Self::style(line, theme.clone())))
+            .collect_vec();
+        let block = Block::bordered()
+            .title(" Diff (Enter: continue, Esc: cancel) ")
+            .style(theme.popup_style());
+
+        f.render_widget(Clear, area);
+        f.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    pub fn handle_event(&mut self, e: &Event) -> Result<()> {
+        if let Event::Key(key) = e {
+            let max_offset = self.lines.len().saturating_sub(self.height);
+
+            match key {
+                KeyEvent {
+                    code: KeyCode::Up, ..
+                } => self.offset = self.offset.saturating_sub(1),
+                KeyEvent {
+                    code: KeyCode::Down,
+                    ..
+                } => self.offset = (self.offset + 1).min(max_offset),
+                KeyEvent {
+                    code: KeyCode::PageUp,
+                    ..
+                } => self.offset = self.offset.saturating_sub(self.height),
+                KeyEvent {
+                    code: KeyCode::PageDown,
+                    ..
+                } => self.offset = (self.offset + self.height).min(max_offset),
+                KeyEvent {
+                    code: KeyCode::Enter,
+                    ..
+                } => self.tx.send(ui::Event::ConfirmReplace)?,
+                _ => (),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn style(line: &str, theme: Arc<dyn Theme>) -> Style {
+        if line.starts_with("diff ") || line.starts_with("--- ") || line.starts_with("+++ ") {
+            theme.file_style()
+        } else if line.starts_with("@@") {
+            theme.line_no_style()
+        } else if line.starts_with('+') {
+            theme.diff_added_style()
+        } else if line.starts_with('-') {
+            theme.diff_removed_style()
+        } else {
+            theme.foreground_style()
+        }
+    }
+}
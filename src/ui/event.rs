@@ -2,23 +2,32 @@ use strum::Display;
 
 use crate::models::{FileResult, Line, SearchCondition, SearchResult};
 use crate::ui;
+use std::sync::Arc;
 
 type ConfirmId = String;
 type SearchConditionCount = usize;
 type Inc = usize;
+type Generation = usize;
 
 #[derive(Display)]
 pub enum Event {
-    ChangeResultLine(FileResult, Line),
-    SearchFinished(SearchResult),
-    ReplaceFinished,
+    ChangeResultLine(Arc<FileResult>, Arc<Line>),
+    SearchProgress(Generation, Arc<FileResult>),
+    FileScanned(Generation, Inc),
+    SearchFinished(Generation, SearchResult),
+    SearchFailed(Generation, String),
+    ReplaceFinished(Vec<String>),
+    ReplaceFailed(String),
     Progress(Inc),
     StartFileSearch(SearchCondition),
     StartResultSearch(SearchCondition, usize),
     StartReplace,
+    ConfirmReplace,
     SelectCondition(SearchCondition),
-    SelectResultLine(FileResult, Line),
-    ReplaceSelectLine(FileResult, Line),
+    SelectAlias(String),
+    SelectQuery(String),
+    SelectResultLine(Arc<FileResult>, Arc<Line>),
+    ReplaceSelectLine(Arc<FileResult>, Arc<Line>),
     DeleteSearchCondition(SearchConditionCount),
     ShowMessage(Option<ui::Status>),
     Error,
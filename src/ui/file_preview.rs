@@ -1,57 +1,253 @@
-use crate::models;
+use super::{syntax, MatchColors, Theme};
+use crate::models::{self, file_result::LineResult, Decoder, MatchResult};
 use anyhow::Result;
 use itertools::Itertools;
+use ratatui::crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
     layout::Rect,
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Paragraph},
     Frame,
 };
+use std::collections::HashMap;
 use std::ops::Range;
-use syntect::{
-    easy::HighlightLines, highlighting::ThemeSet, parsing::SyntaxSet, util::LinesWithEndings,
-};
+use std::sync::Arc;
+use syntect::{easy::HighlightLines, parsing::SyntaxReference, util::LinesWithEndings};
 use syntect_tui::into_span;
 
 #[derive(Clone)]
 pub struct FilePreview {
     file_preview: models::FilePreview,
+    syntax: &'static SyntaxReference,
+    theme: &'static syntect::highlighting::Theme,
+    transforms: HashMap<usize, String>,
+    matches: HashMap<usize, Vec<MatchResult>>,
+    show_transforms: bool,
+    scroll: isize,
+    height: usize,
 }
 
 impl FilePreview {
-    pub fn new(file_path: String) -> Result<Self> {
-        models::FilePreview::new(file_path).map(|file_preview| FilePreview { file_preview })
+    pub fn new(file_path: String, decoder: &Decoder, theme: Option<&str>) -> Result<Self> {
+        let theme = syntax::theme(theme.unwrap_or(syntax::DEFAULT_THEME))?;
+
+        models::FilePreview::new(file_path.clone(), decoder).map(|file_preview| FilePreview {
+            syntax: syntax::detect(&file_path, &file_preview.lines(Range { start: 0, end: 1 })),
+            theme,
+            file_preview,
+            transforms: HashMap::new(),
+            matches: HashMap::new(),
+            show_transforms: false,
+            scroll: 0,
+            height: 0,
+        })
+    }
+
+    /// Keeps the matches of the file and its lines with the pending transforms applied, by line number.
+    pub fn set_result(&mut self, file: &models::FileResult) {
+        self.scroll = 0;
+        self.matches = file
+            .lines
+            .iter()
+            .filter_map(|line| match line {
+                LineResult::Line(line) if !line.matches().is_empty() => {
+                    Some((line.line_no, line.matches().clone()))
+                }
+                _ => None,
+            })
+            .collect();
+        self.transforms = file
+            .lines
+            .iter()
+            .filter_map(|line| match line {
+                LineResult::Line(line) if line.contains_transformed() => Some((
+                    line.line_no,
+                    line.apply_transforms(&line.text).replace('\t', " "),
+                )),
+                _ => None,
+            })
+            .collect();
+    }
+
+    pub fn is_showing_transforms(&self) -> bool {
+        self.show_transforms
+    }
+
+    pub fn toggle_transforms(&mut self) {
+        self.show_transforms = !self.show_transforms;
     }
 
     pub fn is_same_file(&self, file_path: String) -> bool {
         self.file_preview.is_same_file(file_path)
     }
 
-    pub fn draw(&self, f: &mut Frame, area: Rect, line_no: usize) {
+    /// Scrolls the preview with Alt and the arrow or page keys, returning false for other keys.
+    pub fn handle_event(&mut self, e: &Event) -> bool {
+        if let Event::Key(KeyEvent {
+            code, modifiers, ..
+        }) = e
+        {
+            if !modifiers.contains(KeyModifiers::ALT) {
+                return false;
+            }
+
+            let page = self.height.max(1) as isize;
+
+            match code {
+                KeyCode::Up => self.scroll -= 1,
+                KeyCode::Down => self.scroll += 1,
+                KeyCode::PageUp => self.scroll -= page,
+                KeyCode::PageDown => self.scroll += page,
+                _ => return false,
+            }
+
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn draw(&mut self, f: &mut Frame, area: Rect, line_no: usize, theme: Arc<dyn Theme>) {
+        self.height = area.height.saturating_sub(2) as usize;
+
         let start = line_no
-            .checked_sub(area.height as usize / 2)
-            .unwrap_or_default();
+            .saturating_sub(area.height as usize / 2)
+            .saturating_add_signed(self.scroll);
         let end = start + area.height as usize;
         let text = self.file_preview.lines(Range { start, end });
+        let raw_lines = self.file_preview.raw_lines(Range { start, end });
 
-        let ps = SyntaxSet::load_defaults_newlines();
-        let ts = ThemeSet::load_defaults();
-        let syntax = ps.find_syntax_by_extension("rs").unwrap();
-        let mut h = HighlightLines::new(syntax, &ts.themes["base16-ocean.dark"]);
+        let mut h = HighlightLines::new(self.syntax, self.theme);
 
         let lines = LinesWithEndings::from(text.as_str())
-            .map(|line| {
-                let line_spans: Vec<Span> = h
-                    .highlight_line(line, &ps)
-                    .unwrap()
-                    .into_iter()
-                    .filter_map(|segment| into_span(segment).ok())
-                    .collect_vec();
-                Line::from(line_spans)
+            .zip(raw_lines)
+            .enumerate()
+            .map(|(i, (line, raw_line))| {
+                let spans = match h.highlight_line(line, syntax::syntax_set()) {
+                    Ok(segments) => segments
+                        .into_iter()
+                        .filter_map(|segment| into_span(segment).ok())
+                        .map(|span| {
+                            let style = theme.adapt(span.style);
+                            span.style(style)
+                        })
+                        .collect_vec(),
+                    Err(_) => vec![Span::raw(line.trim_end_matches(['\r', '\n']))],
+                };
+                let spans = match self.matches.get(&(start + i + 1)) {
+                    Some(matches) => Self::overlay(spans, raw_line, matches),
+                    None => spans,
+                };
+
+                if start + i + 1 == line_no {
+                    Line::from(
+                        spans
+                            .into_iter()
+                            .map(|span| span.patch_style(theme.highlight_style()))
+                            .collect_vec(),
+                    )
+                    .style(theme.highlight_style())
+                } else {
+                    Line::from(spans)
+                }
             })
             .collect_vec();
-        let text = Paragraph::new(lines).block(Block::bordered().title("File Preview"));
+        let (lines, title) = if self.show_transforms {
+            (
+                self.with_transforms(lines, &text, start, theme),
+                "File Preview (transforms)",
+            )
+        } else {
+            (lines, "File Preview")
+        };
+        let text = Paragraph::new(lines).block(Block::bordered().title(title));
 
         f.render_widget(text, area);
     }
+
+    /// Splits the highlighted spans at the match ranges and colors the matches by condition.
+    fn overlay<'a>(spans: Vec<Span<'a>>, raw_line: &str, matches: &[MatchResult]) -> Vec<Span<'a>> {
+        let ranges = matches
+            .iter()
+            .map(|m| {
+                let style = Style::default()
+                    .fg(MatchColors::get_color(m.index()))
+                    .add_modifier(if m.is_transformed() {
+                        Modifier::UNDERLINED
+                    } else {
+                        Modifier::BOLD
+                    });
+                let range = m.range();
+
+                (
+                    models::FilePreview::expand_offset(raw_line, range.start)
+                        ..models::FilePreview::expand_offset(raw_line, range.end),
+                    style,
+                )
+            })
+            .collect_vec();
+        let mut offset = 0;
+
+        spans
+            .into_iter()
+            .flat_map(|span| {
+                let content = span.content.to_string();
+                let span_start = offset;
+                offset += content.len();
+
+                let mut bounds = ranges
+                    .iter()
+                    .flat_map(|(r, _)| [r.start, r.end])
+                    .filter(|b| *b > span_start && *b < offset)
+                    .map(|b| b - span_start)
+                    .filter(|b| content.is_char_boundary(*b))
+                    .collect_vec();
+                bounds.sort();
+                bounds.dedup();
+
+                [0].into_iter()
+                    .chain(bounds.clone())
+                    .zip(bounds.into_iter().chain([content.len()]))
+                    .map(|(from, to)| {
+                        let style = ranges
+                            .iter()
+                            .filter(|(r, _)| {
+                                r.start <= span_start + from && span_start + to <= r.end
+                            })
+                            .fold(span.style, |style, (_, s)| style.patch(*s));
+
+                        Span::styled(content[from..to].to_string(), style)
+                    })
+                    .collect_vec()
+            })
+            .collect_vec()
+    }
+
+    /// Shows each transformed line as the original and the transformed line, like an inline diff.
+    fn with_transforms<'a>(
+        &self,
+        lines: Vec<Line<'a>>,
+        text: &str,
+        start: usize,
+        theme: Arc<dyn Theme>,
+    ) -> Vec<Line<'a>> {
+        lines
+            .into_iter()
+            .zip(text.lines())
+            .enumerate()
+            .flat_map(
+                |(i, (line, original))| match self.transforms.get(&(start + i + 1)) {
+                    Some(transformed) => vec![
+                        Line::styled(format!("- {}", original), theme.diff_removed_style()),
+                        Line::styled(format!("+ {}", transformed), theme.diff_added_style()),
+                    ],
+                    None => vec![Line::from(itertools::concat(vec![
+                        vec![Span::raw("  ")],
+                        line.spans,
+                    ]))],
+                },
+            )
+            .collect_vec()
+    }
 }
@@ -19,10 +19,18 @@ impl Help {
             Self::shortcut("Tab", "select command."),
             Self::shortcut_with_modifier("Ctrl", "c", "quit."),
             Self::shortcut_with_modifier("Ctrl", "e", "replace all."),
+            Self::shortcut_with_modifier("Ctrl", "g", "abort search."),
             Self::shortcut_with_modifier("Ctrl", "n", "copy result."),
-            Self::shortcut_with_modifier("Ctrl", "r", "replace on selected rows."),
+            Self::shortcut_with_modifier("Ctrl", "o", "replace on selected rows."),
+            Self::shortcut_with_modifier("Ctrl", "p", "preview transforms."),
+            Self::shortcut_with_modifier("Ctrl", "r", "search history."),
+            Self::shortcut_with_modifier("Ctrl", "s", "mark row."),
+            Self::shortcut_with_modifier("Ctrl", "t", "mark file."),
             Self::shortcut_with_modifier("Ctrl", "v", "show file preview."),
+            Self::shortcut_with_modifier("Ctrl", "x", "invert marks."),
             Self::shortcut_with_modifier("Ctrl", "y", "copy command."),
+            Self::shortcut_with_modifier("Ctrl", "z", "undo replace."),
+            Self::shortcut_with_modifier("Alt", "↑↓", "scroll preview."),
         ]));
 
         f.render_widget(Paragraph::new(line), area);
@@ -32,6 +32,13 @@ impl Input {
         }
     }
 
+    pub fn with_value(self, value: String) -> Self {
+        Self {
+            input: self.input.with_value(value),
+            ..self
+        }
+    }
+
     pub fn move_cursor(&self, index: usize) -> Self {
         Self {
             input: self.input.clone().with_cursor(index),
@@ -79,7 +86,7 @@ impl Input {
                     code: KeyCode::Enter,
                     ..
                 } => match &self.input_state {
-                    InputState::Input(Ok(_)) if !self.input.value().is_empty() => {
+                    InputState::Input(_) if !self.input.value().is_empty() => {
                         match self.input.value().parse() {
                             Ok(c) => {
                                 self.input_state = InputState::Entered(c);
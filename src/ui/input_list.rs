@@ -1,5 +1,5 @@
 use super::{Icon, Input, MatchColors, Theme};
-use crate::{models::SearchCondition, ui};
+use crate::{models::SearchCondition, parser::Aliases, ui};
 use itertools::Itertools;
 use ratatui::crossterm::event::{Event, KeyCode, KeyEvent};
 use ratatui::{
@@ -14,19 +14,52 @@ use std::sync::{mpsc, Arc};
 #[derive(Clone, Debug)]
 pub struct InputList {
     current_input: Input,
+    aliases: Arc<Aliases>,
     tx: mpsc::Sender<ui::Event>,
+    history: Vec<String>,
+    recalled: Option<usize>,
     pub entered_list: Vec<Input>,
 }
 
 impl InputList {
-    pub fn new(entered_list: Vec<Input>, tx: mpsc::Sender<ui::Event>) -> Self {
+    pub fn new(
+        entered_list: Vec<Input>,
+        aliases: Arc<Aliases>,
+        tx: mpsc::Sender<ui::Event>,
+    ) -> Self {
         InputList {
             current_input: Input::new(tx.clone()),
             entered_list,
+            aliases,
             tx,
+            history: Vec::new(),
+            recalled: None,
         }
     }
 
+    pub fn set_aliases(&mut self, aliases: Arc<Aliases>) {
+        self.aliases = aliases;
+    }
+
+    pub fn set_history(&mut self, history: &[String]) {
+        self.history = history.to_vec();
+        self.recalled = None;
+    }
+
+    /// Replaces the whole pipeline, e.g. with a query from the history.
+    pub fn set_conditions(&mut self, conditions: Vec<SearchCondition>) {
+        self.entered_list = conditions
+            .into_iter()
+            .map(|c| Input::entered(c, self.tx.clone()))
+            .collect();
+        self.current_input = Input::new(self.tx.clone());
+        self.recalled = None;
+    }
+
+    pub fn query(&self) -> String {
+        self.conditions().iter().map(|c| c.to_string()).join(" | ")
+    }
+
     pub fn set_current_condition(&mut self, condition: SearchCondition) {
         if !&condition.has_args() {
             self.current_input = Input::entered(condition, self.tx.clone());
@@ -43,6 +76,13 @@ impl InputList {
         self.current_input.value()
     }
 
+    pub fn conditions(&self) -> Vec<SearchCondition> {
+        self.entered_list
+            .iter()
+            .filter_map(|i| i.entered_condition())
+            .collect()
+    }
+
     pub fn has_transform(&self) -> bool {
         self.entered_list.iter().any(|i| i.has_transform())
     }
@@ -83,9 +123,7 @@ impl InputList {
         let [icon_rect, entered_rect, current_input_rect] = Layout::horizontal([
             Constraint::Length(2),
             Constraint::Length(
-                ((entered_len)
-                    .checked_sub(self.entered_list.len().checked_sub(1).unwrap_or_default())
-                    .unwrap_or_default()) as u16,
+                entered_len.saturating_sub(self.entered_list.len().saturating_sub(1)) as u16,
             ),
             Constraint::Percentage(100),
         ])
@@ -112,6 +150,10 @@ impl InputList {
     }
 
     pub fn handle_event(&mut self, e: &Event) -> bool {
+        if self.handle_history_event(e) || self.handle_alias_event(e) {
+            return true;
+        }
+
         let processed = self.current_input.handle_event(e);
 
         if let Event::Key(key) = e {
@@ -143,6 +185,87 @@ impl InputList {
         }
     }
 
+    /// Recalls the history with Up and Down while no condition has been entered.
+    /// Enter on a recalled query runs it as a whole pipeline.
+    fn handle_history_event(&mut self, e: &Event) -> bool {
+        if !self.entered_list.is_empty() {
+            return false;
+        }
+
+        match e {
+            Event::Key(KeyEvent {
+                code: KeyCode::Up, ..
+            }) if !self.history.is_empty() => {
+                let index = self
+                    .recalled
+                    .map(|i| i.saturating_sub(1))
+                    .unwrap_or(self.history.len() - 1);
+                self.recall(Some(index));
+                true
+            }
+            Event::Key(KeyEvent {
+                code: KeyCode::Down,
+                ..
+            }) if self.recalled.is_some() => {
+                let index = self
+                    .recalled
+                    .map(|i| i + 1)
+                    .filter(|i| *i < self.history.len());
+                self.recall(index);
+                true
+            }
+            Event::Key(KeyEvent {
+                code: KeyCode::Enter,
+                ..
+            }) if self.recalled.is_some() && !self.current_input.value().is_empty() => {
+                self.recalled = None;
+                self.tx
+                    .send(ui::Event::SelectQuery(
+                        self.current_input.value().to_string(),
+                    ))
+                    .ok();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Appends a saved query or alias to the pipeline and runs it.
+    pub fn enter_alias(&self, alias: &str) {
+        let query = self
+            .conditions()
+            .iter()
+            .map(|c| c.to_string())
+            .chain([alias.trim().to_string()])
+            .join(" | ");
+
+        self.tx.send(ui::Event::SelectQuery(query)).ok();
+    }
+
+    /// Runs a saved query or alias entered as the next stage, which may expand to several conditions.
+    fn handle_alias_event(&mut self, e: &Event) -> bool {
+        match e {
+            Event::Key(KeyEvent {
+                code: KeyCode::Enter,
+                ..
+            }) if self.aliases.is_invocation(self.current_input.value()) => {
+                self.enter_alias(self.current_input.value());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn recall(&mut self, index: Option<usize>) {
+        let value = index
+            .and_then(|i| self.history.get(i))
+            .cloned()
+            .unwrap_or_default();
+
+        self.recalled = index;
+        self.current_input = Input::new(self.tx.clone()).with_value(value);
+    }
+
     fn update_entered_list(&mut self) -> bool {
         if self.current_input.is_entered() {
             let i = self.current_input.clone();
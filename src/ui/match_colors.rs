@@ -20,11 +20,11 @@ const COLORS: [Color; 12] = [
 
 impl MatchColors {
     pub fn get_color(index: usize) -> Color {
-        COLORS[index.checked_sub(1).unwrap_or_default() % COLORS.len()]
+        COLORS[index.saturating_sub(1) % COLORS.len()]
     }
 
     pub fn get_cli_color(index: usize) -> colored::Color {
-        match COLORS[index.checked_sub(1).unwrap_or_default() % COLORS.len()] {
+        match COLORS[index.saturating_sub(1) % COLORS.len()] {
             Color::Yellow => colored::Color::Yellow,
             Color::Blue => colored::Color::Blue,
             Color::Green => colored::Color::Green,
@@ -62,7 +62,7 @@ impl Display for SearchCondition<'_> {
             _ if self.condition.is_line_filter() => {
                 format!("{} {}", self.icon.line(), &self.condition.to_string())
             }
-            _ if self.condition.is_filter() => {
+            _ if self.condition.is_filter() || self.condition.is_file_filter() => {
                 format!("{} {}", self.icon.filter(), &self.condition.to_string())
             }
             _ if self.condition.is_transform() => {
@@ -12,11 +12,16 @@ use ratatui::{
     widgets::{List, ListItem, ListState},
     Frame,
 };
+use std::collections::HashSet;
 use std::sync::{mpsc, Arc};
 
+const MARK: &str = "● ";
+const NO_MARK: &str = "  ";
+
 #[derive(Clone)]
 pub struct SearchResult {
     rows: Vec<Row>,
+    marks: HashSet<(String, usize)>,
     state: ListState,
     config: SearchResultConfig,
     tx: mpsc::Sender<ui::Event>,
@@ -24,83 +29,173 @@ pub struct SearchResult {
 
 #[derive(Clone)]
 enum Row {
-    File(models::FileResult),
-    Line(models::FileResult, models::Line),
+    File(Arc<models::FileResult>),
+    Line(Arc<models::FileResult>, Arc<models::Line>),
     Separator,
 }
 
 impl SearchResult {
     pub fn new(
-        files: &[models::FileResult],
+        files: &[Arc<models::FileResult>],
         config: SearchResultConfig,
         tx: mpsc::Sender<ui::Event>,
     ) -> Self {
         let rows = files
             .iter()
-            .flat_map(|file| {
-                let rows = if config.vimgrep {
-                    file.lines
-                        .iter()
-                        .flat_map(|line| {
-                            if let LineResult::Line(line) = line {
-                                if line.is_filtered() {
-                                    Vec::new()
-                                } else {
-                                    line.matches()
-                                        .iter()
-                                        .map(|m| {
-                                            Row::Line(
-                                                file.clone(),
-                                                models::Line::new(
-                                                    line.line_no,
-                                                    line.text.clone(),
-                                                    vec![m.clone()],
-                                                    line.is_filtered(),
-                                                ),
-                                            )
-                                        })
-                                        .collect::<Vec<_>>()
-                                }
-                            } else {
-                                vec![Row::Separator]
-                            }
-                        })
-                        .collect::<Vec<_>>()
-                } else {
-                    file.lines
-                        .iter()
-                        .filter_map(|line| {
-                            if let LineResult::Line(line) = line {
-                                if line.is_filtered() {
-                                    None
-                                } else {
-                                    Some(Row::Line(file.clone(), line.clone()))
-                                }
-                            } else {
-                                Some(Row::Separator)
-                            }
-                        })
-                        .collect::<Vec<_>>()
-                };
-
-                if rows.is_empty() {
-                    Vec::new()
-                } else {
-                    itertools::concat(vec![vec![Row::File(file.clone())], rows])
-                }
-            })
+            .flat_map(|file| SearchResult::rows(file, &config))
             .collect();
         let mut state = ListState::default();
         state.select(if files.is_empty() { None } else { Some(1) });
 
         Self {
             rows,
+            marks: HashSet::new(),
             state,
             config,
             tx,
         }
     }
 
+    /// Lines marked for replacement as file path and line number, all lines if empty.
+    pub fn marks(&self) -> &HashSet<(String, usize)> {
+        &self.marks
+    }
+
+    fn toggle_mark(&mut self) {
+        if let Some((file, line)) = self.selected() {
+            let mark = (file.file_path.clone(), line.line_no);
+
+            if !self.marks.remove(&mark) {
+                self.marks.insert(mark);
+            }
+
+            self.next();
+        }
+    }
+
+    /// Marks every line of the selected file, or unmarks them if they are all marked.
+    fn toggle_file_marks(&mut self) {
+        if let Some((file, _)) = self.selected() {
+            let marks = self
+                .line_marks()
+                .filter(|(path, _)| *path == file.file_path)
+                .collect::<HashSet<_>>();
+
+            if marks.is_subset(&self.marks) {
+                self.marks.retain(|mark| !marks.contains(mark));
+            } else {
+                self.marks.extend(marks);
+            }
+        }
+    }
+
+    fn invert_marks(&mut self) {
+        self.marks = self
+            .line_marks()
+            .filter(|mark| !self.marks.contains(mark))
+            .collect();
+    }
+
+    fn line_marks(&self) -> impl Iterator<Item = (String, usize)> + '_ {
+        self.rows.iter().filter_map(|row| match row {
+            Row::Line(f, l) => Some((f.file_path.clone(), l.line_no)),
+            _ => None,
+        })
+    }
+
+    fn mark_span(
+        &self,
+        file: &models::FileResult,
+        line: &models::Line,
+        theme: &Arc<dyn Theme>,
+    ) -> Span<'static> {
+        if self.marks.contains(&(file.file_path.clone(), line.line_no)) {
+            Span::styled(MARK, theme.transform_style())
+        } else {
+            Span::raw(NO_MARK)
+        }
+    }
+
+    /// Appends the rows of a file that finished while the search is still running,
+    /// returning false if all of its lines were filtered out.
+    pub fn push(&mut self, file: &Arc<models::FileResult>) -> bool {
+        let rows = SearchResult::rows(file, &self.config);
+
+        if rows.is_empty() {
+            return false;
+        }
+
+        if self.state.selected().is_none() {
+            self.state.select(Some(self.rows.len() + 1));
+        }
+
+        self.rows.extend(rows);
+        true
+    }
+
+    /// Selects the given line if it is still in the result, e.g. after the result is rebuilt.
+    pub fn select_line(&mut self, file: &models::FileResult, line: &models::Line) {
+        if let Some(i) = self.rows.iter().position(|r| match r {
+            Row::Line(f, l) => f.file_path == file.file_path && l.line_no == line.line_no,
+            _ => false,
+        }) {
+            self.state.select(Some(i));
+        }
+    }
+
+    fn rows(file: &Arc<models::FileResult>, config: &SearchResultConfig) -> Vec<Row> {
+        let rows = if config.vimgrep {
+            file.lines
+                .iter()
+                .flat_map(|line| {
+                    if let LineResult::Line(line) = line {
+                        if line.is_filtered() {
+                            Vec::new()
+                        } else {
+                            line.matches()
+                                .iter()
+                                .map(|m| {
+                                    Row::Line(
+                                        Arc::clone(file),
+                                        Arc::new(models::Line::new(
+                                            line.line_no,
+                                            line.text.clone(),
+                                            vec![m.clone()],
+                                            line.is_filtered(),
+                                        )),
+                                    )
+                                })
+                                .collect::<Vec<_>>()
+                        }
+                    } else {
+                        vec![Row::Separator]
+                    }
+                })
+                .collect::<Vec<_>>()
+        } else {
+            file.lines
+                .iter()
+                .filter_map(|line| {
+                    if let LineResult::Line(line) = line {
+                        if line.is_filtered() {
+                            None
+                        } else {
+                            Some(Row::Line(Arc::clone(file), Arc::clone(line)))
+                        }
+                    } else {
+                        Some(Row::Separator)
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+
+        if !rows.iter().any(|row| matches!(row, Row::Line(_, _))) {
+            Vec::new()
+        } else {
+            itertools::concat(vec![vec![Row::File(Arc::clone(file))], rows])
+        }
+    }
+
     fn next(&mut self) {
         if self.rows.is_empty() {
             return;
@@ -177,16 +272,16 @@ impl SearchResult {
             .state
             .selected()
             .and_then(|i| {
-                let v = match self.rows[i.checked_sub(1).unwrap_or_default()] {
-                    Row::File(_) => i.checked_sub(2).unwrap_or_default(),
-                    _ => i.checked_sub(1).unwrap_or_default(),
+                let v = match self.rows[i.saturating_sub(1)] {
+                    Row::File(_) => i.saturating_sub(2),
+                    _ => i.saturating_sub(1),
                 };
 
                 self.rows[..v]
                     .iter()
                     .rev()
                     .position(|r| matches!(r, Row::File(_)))
-                    .map(|p| (v - p))
+                    .map(|p| v - p)
             })
             .unwrap_or(self.rows.len() - 1);
 
@@ -207,7 +302,7 @@ impl SearchResult {
         let prev_index = self
             .state
             .selected()
-            .and_then(|i| match self.rows[i.checked_sub(1).unwrap_or_default()] {
+            .and_then(|i| match self.rows[i.saturating_sub(1)] {
                 Row::File(_) => i.checked_sub(2),
                 Row::Line(_, _) => i.checked_sub(1),
                 Row::Separator => i.checked_sub(2),
@@ -226,7 +321,7 @@ impl SearchResult {
             .ok();
     }
 
-    pub fn selected(&mut self) -> Option<(models::FileResult, models::Line)> {
+    pub fn selected(&mut self) -> Option<(Arc<models::FileResult>, Arc<models::Line>)> {
         if self.rows.is_empty() {
             None
         } else {
@@ -254,6 +349,7 @@ impl SearchResult {
                 Row::File(_) => None,
                 Row::Line(f, line) if self.config.vimgrep => {
                     let line_no = vec![
+                        self.mark_span(f, line, &theme),
                         Span::styled(f.file_path.clone(), theme.file_style()),
                         Span::styled(
                             format!(
@@ -273,7 +369,7 @@ impl SearchResult {
                         line.tokens()
                             .into_iter()
                             .flat_map(|(token, m)| match m {
-                                Some(MatchResult::Found(_, _, index)) => {
+                                Some(MatchResult::Found(_, _, index, _)) => {
                                     vec![Span::styled(
                                         token,
                                         Style::default()
@@ -281,7 +377,7 @@ impl SearchResult {
                                             .add_modifier(Modifier::BOLD),
                                     )]
                                 }
-                                Some(MatchResult::Transformed(text, _, index)) => {
+                                Some(MatchResult::Transformed(text, _, index, _)) => {
                                     vec![
                                         Span::styled(token, theme.disabled_style()),
                                         Span::styled(
@@ -298,14 +394,14 @@ impl SearchResult {
                             .collect::<Vec<_>>(),
                     ]))))
                 }
-                Row::Line(_, line) => {
+                Row::Line(f, line) => {
                     let line_no = if self.config.no_line_no {
-                        Vec::new()
+                        vec![self.mark_span(f, line, &theme)]
                     } else {
-                        vec![Span::styled(
-                            format!(" {}: ", line.line_no + 1),
-                            theme.line_no_style(),
-                        )]
+                        vec![
+                            self.mark_span(f, line, &theme),
+                            Span::styled(format!("{}: ", line.line_no + 1), theme.line_no_style()),
+                        ]
                     };
 
                     Some(ListItem::new(Line::from(itertools::concat(vec![
@@ -313,7 +409,7 @@ impl SearchResult {
                         line.tokens()
                             .into_iter()
                             .flat_map(|(token, m)| match m {
-                                Some(MatchResult::Found(_, _, index)) => {
+                                Some(MatchResult::Found(_, _, index, _)) => {
                                     vec![Span::styled(
                                         token,
                                         Style::default()
@@ -321,7 +417,7 @@ impl SearchResult {
                                             .add_modifier(Modifier::BOLD),
                                     )]
                                 }
-                                Some(MatchResult::Transformed(text, _, index)) => {
+                                Some(MatchResult::Transformed(text, _, index, _)) => {
                                     vec![
                                         Span::styled(token, theme.disabled_style()),
                                         Span::styled(
@@ -383,7 +479,7 @@ impl SearchResult {
                     ..
                 } => self.next_file(),
                 KeyEvent {
-                    code: KeyCode::Char('r'),
+                    code: KeyCode::Char('o'),
                     modifiers: KeyModifiers::CONTROL,
                     ..
                 } => {
@@ -398,6 +494,21 @@ impl SearchResult {
                             _ => None,
                         });
                 }
+                KeyEvent {
+                    code: KeyCode::Char('s'),
+                    modifiers: KeyModifiers::CONTROL,
+                    ..
+                } => self.toggle_mark(),
+                KeyEvent {
+                    code: KeyCode::Char('t'),
+                    modifiers: KeyModifiers::CONTROL,
+                    ..
+                } => self.toggle_file_marks(),
+                KeyEvent {
+                    code: KeyCode::Char('x'),
+                    modifiers: KeyModifiers::CONTROL,
+                    ..
+                } => self.invert_marks(),
                 KeyEvent {
                     code: KeyCode::Enter,
                     ..
@@ -1,4 +1,4 @@
-use crate::{models::SearchCondition, ui};
+use crate::{models::SearchCondition, parser::Aliases, ui};
 use ratatui::crossterm::event::{Event, KeyCode, KeyEvent};
 use ratatui::{
     layout::{Constraint, Layout, Rect},
@@ -16,13 +16,15 @@ use super::{Icon, Theme};
 pub struct SelectCondition {
     conditions: Vec<SearchCondition>,
     filtered_conditions: Vec<SearchCondition>,
+    queries: Vec<(String, String)>,
+    filtered_queries: Vec<(String, String)>,
     input: Input,
     state: ListState,
     tx: mpsc::Sender<ui::Event>,
 }
 
 impl SelectCondition {
-    pub fn new(value: &str, tx: mpsc::Sender<ui::Event>) -> Self {
+    pub fn new(value: &str, aliases: &Aliases, tx: mpsc::Sender<ui::Event>) -> Self {
         let mut state = ListState::default();
 
         state.select(Some(0));
@@ -32,6 +34,12 @@ impl SelectCondition {
                 .map(|c| c.with_value(value.to_string()).unwrap_or(c))
                 .collect(),
             filtered_conditions: Vec::new(),
+            queries: aliases
+                .queries()
+                .iter()
+                .map(|(name, query)| (format!("@{}", name), query.clone()))
+                .collect(),
+            filtered_queries: Vec::new(),
             input: Input::default(),
             state,
             tx,
@@ -75,12 +83,18 @@ impl SelectCondition {
                 }
             })
             .collect();
+        self.filtered_queries = self
+            .queries
+            .iter()
+            .filter(|(name, _)| name.contains(self.input.value()))
+            .cloned()
+            .collect();
 
         f.render_widget(Clear, area);
         f.render_widget(input, input_area);
         f.render_widget(block, area);
 
-        if self.filtered_conditions.is_empty() {
+        if self.len() == 0 {
             f.render_widget(
                 Paragraph::new("Not found")
                     .block(Block::default().padding(Padding::new(1, 1, 0, 0)))
@@ -100,7 +114,10 @@ impl SelectCondition {
 
                     let style = if condition.is_matcher() {
                         theme.match_style()
-                    } else if condition.is_filter() || condition.is_line_filter() {
+                    } else if condition.is_filter()
+                        || condition.is_line_filter()
+                        || condition.is_file_filter()
+                    {
                         theme.filter_style()
                     } else if condition.is_transform() {
                         theme.transform_style()
@@ -110,6 +127,12 @@ impl SelectCondition {
 
                     ListItem::new(Line::from(Span::styled(s, style)))
                 })
+                .chain(self.filtered_queries.iter().map(|(name, query)| {
+                    ListItem::new(Line::from(vec![
+                        Span::styled(format!("{} ", name), theme.match_style()),
+                        Span::styled(query.as_str(), theme.foreground_style()),
+                    ]))
+                }))
                 .collect::<Vec<_>>();
             let list = List::new(list_items)
                 .highlight_style(theme.highlight_style())
@@ -120,6 +143,10 @@ impl SelectCondition {
         f.set_cursor_position((area.x + (self.input.visual_cursor()) as u16 + 1, area.y + 1))
     }
 
+    fn len(&self) -> usize {
+        self.filtered_conditions.len() + self.filtered_queries.len()
+    }
+
     pub fn handle_event(&mut self, e: &Event) {
         if let Event::Key(key) = e {
             match key {
@@ -131,9 +158,7 @@ impl SelectCondition {
                         .state
                         .selected()
                         .map(|i| {
-                            if i == self.filtered_conditions.len() - 1
-                                && !self.conditions.is_empty()
-                            {
+                            if i == self.len() - 1 && !self.conditions.is_empty() {
                                 1
                             } else {
                                 i + 1
@@ -150,7 +175,7 @@ impl SelectCondition {
                         .state
                         .selected()
                         .and_then(|i| i.checked_sub(1))
-                        .unwrap_or(self.filtered_conditions.len() - 1);
+                        .unwrap_or(self.len() - 1);
 
                     self.state.select(Some(prev_index));
                 }
@@ -158,10 +183,19 @@ impl SelectCondition {
                     code: KeyCode::Enter,
                     ..
                 } => {
-                    self.state
-                        .selected()
-                        .and_then(|i| self.filtered_conditions.get(i))
-                        .and_then(|c| self.tx.send(ui::Event::SelectCondition(c.clone())).ok());
+                    match self.state.selected() {
+                        Some(i) if i < self.filtered_conditions.len() => self
+                            .filtered_conditions
+                            .get(i)
+                            .and_then(|c| self.tx.send(ui::Event::SelectCondition(c.clone())).ok()),
+                        Some(i) => self
+                            .filtered_queries
+                            .get(i - self.filtered_conditions.len())
+                            .and_then(|(name, _)| {
+                                self.tx.send(ui::Event::SelectAlias(name.clone())).ok()
+                            }),
+                        None => None,
+                    };
                 }
                 _ => {
                     self.state.select(Some(0));
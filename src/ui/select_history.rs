@@ -0,0 +1,136 @@
+use crate::{models::History, ui};
+use ratatui::crossterm::event::{Event, KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    text::{Line, Span},
+    widgets::{Block, Clear, List, ListItem, ListState, Padding, Paragraph},
+    Frame,
+};
+use std::sync::{mpsc, Arc};
+use tui_input::{backend::crossterm::EventHandler, Input};
+
+use super::Theme;
+
+#[derive(Clone, Debug)]
+pub struct SelectHistory {
+    history: History,
+    filtered_entries: Vec<String>,
+    input: Input,
+    state: ListState,
+    tx: mpsc::Sender<ui::Event>,
+}
+
+impl SelectHistory {
+    pub fn new(history: History, tx: mpsc::Sender<ui::Event>) -> Self {
+        let mut state = ListState::default();
+
+        state.select(Some(0));
+        SelectHistory {
+            history,
+            filtered_entries: Vec::new(),
+            input: Input::default(),
+            state,
+            tx,
+        }
+    }
+
+    pub fn draw(&mut self, f: &mut Frame, area: Rect, theme: Arc<dyn Theme>) {
+        let [input_area, list_area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Length(area.height - 3)])
+                .areas(area);
+
+        let block = Block::bordered()
+            .title("Search history")
+            .style(theme.popup_style());
+
+        let input = Paragraph::new(self.input.value())
+            .block(Block::bordered().border_style(theme.border_style()))
+            .style(theme.foreground_style());
+
+        self.filtered_entries = self
+            .history
+            .search(self.input.value())
+            .into_iter()
+            .map(|entry| entry.to_string())
+            .collect();
+
+        f.render_widget(Clear, area);
+        f.render_widget(input, input_area);
+        f.render_widget(block, area);
+
+        if self.filtered_entries.is_empty() {
+            f.render_widget(
+                Paragraph::new("Not found")
+                    .block(Block::default().padding(Padding::new(1, 1, 0, 0)))
+                    .style(theme.foreground_style()),
+                list_area,
+            );
+        } else {
+            let list_items = self
+                .filtered_entries
+                .iter()
+                .map(|entry| {
+                    ListItem::new(Line::from(Span::styled(
+                        entry.as_str(),
+                        theme.foreground_style(),
+                    )))
+                })
+                .collect::<Vec<_>>();
+            let list = List::new(list_items)
+                .highlight_style(theme.highlight_style())
+                .block(Block::default().padding(Padding::new(1, 1, 0, 0)));
+            f.render_stateful_widget(list, list_area, &mut self.state);
+        }
+
+        f.set_cursor_position((area.x + (self.input.visual_cursor()) as u16 + 1, area.y + 1))
+    }
+
+    pub fn handle_event(&mut self, e: &Event) {
+        if let Event::Key(key) = e {
+            match key {
+                KeyEvent {
+                    code: KeyCode::Down,
+                    ..
+                } => {
+                    let next_index = self
+                        .state
+                        .selected()
+                        .map(|i| {
+                            if i + 1 >= self.filtered_entries.len() {
+                                0
+                            } else {
+                                i + 1
+                            }
+                        })
+                        .unwrap_or(0);
+
+                    self.state.select(Some(next_index));
+                }
+                KeyEvent {
+                    code: KeyCode::Up, ..
+                } => {
+                    let prev_index = self
+                        .state
+                        .selected()
+                        .and_then(|i| i.checked_sub(1))
+                        .unwrap_or(self.filtered_entries.len().saturating_sub(1));
+
+                    self.state.select(Some(prev_index));
+                }
+                KeyEvent {
+                    code: KeyCode::Enter,
+                    ..
+                } => {
+                    self.state
+                        .selected()
+                        .and_then(|i| self.filtered_entries.get(i))
+                        .and_then(|q| self.tx.send(ui::Event::SelectQuery(q.clone())).ok());
+                }
+                _ => {
+                    self.state.select(Some(0));
+                    self.input.handle_event(e);
+                }
+            }
+        }
+    }
+}
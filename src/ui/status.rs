@@ -65,7 +65,18 @@ impl Status {
                 let line = Line::from(vec![
                     Span::styled(" INFO ", theme.info_style()),
                     Span::styled(
-                        format!(" Found {} matches in {} files", s.match_count, s.file_count),
+                        match s.skipped_summary() {
+                            Some(skipped) => format!(
+                                " Found {} matches in {} files, skipped {}",
+                                s.match_count, s.file_count, skipped
+                            ),
+                            None => {
+                                format!(
+                                    " Found {} matches in {} files",
+                                    s.match_count, s.file_count
+                                )
+                            }
+                        },
                         theme.status_style(),
                     ),
                 ]);
@@ -0,0 +1,91 @@
+use crate::models::config::config_dir;
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use std::path::Path;
+use std::sync::OnceLock;
+use syntect::{
+    highlighting::{Theme, ThemeSet},
+    parsing::{SyntaxReference, SyntaxSet},
+};
+
+pub const DEFAULT_THEME: &str = "base16-ocean.dark";
+
+/// Extensions without a bundled syntax that are close enough to one that is.
+const ALIASES: &[(&str, &str)] = &[
+    ("ts", "js"),
+    ("tsx", "js"),
+    ("mts", "js"),
+    ("cts", "js"),
+    ("jsx", "js"),
+    ("mjs", "js"),
+    ("cjs", "js"),
+];
+
+static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+static THEME_SET: OnceLock<ThemeSet> = OnceLock::new();
+
+/// The bundled syntaxes plus any `.sublime-syntax` files in `<config dir>/syntaxes`, loaded once.
+pub fn syntax_set() -> &'static SyntaxSet {
+    SYNTAX_SET.get_or_init(|| {
+        let mut builder = SyntaxSet::load_defaults_newlines().into_builder();
+
+        if let Some(dir) = config_dir().map(|dir| dir.join("syntaxes")) {
+            if dir.is_dir() {
+                builder.add_from_folder(dir, true).ok();
+            }
+        }
+
+        builder.build()
+    })
+}
+
+/// The bundled themes plus any `.tmTheme` files in `<config dir>/themes`, loaded once.
+pub fn theme_set() -> &'static ThemeSet {
+    THEME_SET.get_or_init(|| {
+        let mut theme_set = ThemeSet::load_defaults();
+
+        if let Some(dir) = config_dir().map(|dir| dir.join("themes")) {
+            if dir.is_dir() {
+                theme_set.add_from_folder(dir).ok();
+            }
+        }
+
+        theme_set
+    })
+}
+
+pub fn theme(name: &str) -> Result<&'static Theme> {
+    theme_set().themes.get(name).ok_or_else(|| {
+        anyhow!(
+            "Unknown preview theme `{}`, expected one of {}",
+            name,
+            theme_set().themes.keys().join(", ")
+        )
+    })
+}
+
+/// Detects the syntax from the file name or extension, then from the first line, e.g. a shebang.
+pub fn detect(file_path: &str, first_line: &str) -> &'static SyntaxReference {
+    let syntax_set = syntax_set();
+    let path = Path::new(file_path);
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default();
+
+    syntax_set
+        .find_syntax_by_extension(file_name)
+        .or_else(|| syntax_set.find_syntax_by_extension(extension))
+        .or_else(|| {
+            ALIASES
+                .iter()
+                .find(|(alias, _)| *alias == extension)
+                .and_then(|(_, extension)| syntax_set.find_syntax_by_extension(extension))
+        })
+        .or_else(|| syntax_set.find_syntax_by_first_line(first_line))
+        .unwrap_or_else(|| syntax_set.find_syntax_plain_text())
+}
@@ -1,5 +1,11 @@
+mod color_support;
+mod custom;
+
 use ratatui::style::{Color, Modifier, Style};
 
+pub use self::color_support::ColorSupport;
+pub use self::custom::CustomTheme;
+
 pub trait Theme {
     fn foreground_style(&self) -> Style;
     fn file_style(&self) -> Style;
@@ -20,6 +26,13 @@ pub trait Theme {
     fn button_style(&self) -> Style;
     fn selected_button_style(&self) -> Style;
     fn progressbar_style(&self) -> Style;
+    fn diff_added_style(&self) -> Style;
+    fn diff_removed_style(&self) -> Style;
+
+    /// Adapts a style from outside the theme, e.g. syntax highlighting, to the terminal.
+    fn adapt(&self, style: Style) -> Style {
+        style
+    }
 }
 
 pub struct Dark;
@@ -112,6 +125,14 @@ impl Theme for Dark {
     fn progressbar_style(&self) -> Style {
         Style::default().fg(Color::Yellow).bg(Color::Gray)
     }
+
+    fn diff_added_style(&self) -> Style {
+        Style::default().fg(Color::Green)
+    }
+
+    fn diff_removed_style(&self) -> Style {
+        Style::default().fg(Color::Red)
+    }
 }
 
 pub struct Light;
@@ -133,7 +154,7 @@ impl Theme for Light {
 
     fn line_no_style(&self) -> Style {
         Style::default()
-            .fg(Color::LightCyan)
+            .fg(Color::Blue)
             .add_modifier(Modifier::BOLD)
     }
 
@@ -152,7 +173,10 @@ impl Theme for Light {
     }
 
     fn error_style(&self) -> Style {
-        Style::default().bg(Color::Red).add_modifier(Modifier::BOLD)
+        Style::default()
+            .bg(Color::Red)
+            .fg(Color::White)
+            .add_modifier(Modifier::BOLD)
     }
 
     fn popup_style(&self) -> Style {
@@ -165,7 +189,7 @@ impl Theme for Light {
 
     fn info_style(&self) -> Style {
         Style::default()
-            .bg(Color::Cyan)
+            .bg(Color::Blue)
             .fg(Color::White)
             .add_modifier(Modifier::BOLD)
     }
@@ -178,19 +202,21 @@ impl Theme for Light {
     }
 
     fn count_style(&self) -> Style {
-        Style::default().fg(Color::Green)
+        Style::default().fg(Color::Rgb(0, 128, 0))
     }
 
     fn match_style(&self) -> Style {
-        Style::default().fg(Color::Yellow)
+        Style::default()
+            .fg(Color::Rgb(175, 95, 0))
+            .add_modifier(Modifier::BOLD)
     }
 
     fn filter_style(&self) -> Style {
-        Style::default().fg(Color::Cyan)
+        Style::default().fg(Color::Blue)
     }
 
     fn transform_style(&self) -> Style {
-        Style::default().fg(Color::Green)
+        Style::default().fg(Color::Rgb(0, 128, 0))
     }
 
     fn button_style(&self) -> Style {
@@ -198,9 +224,18 @@ impl Theme for Light {
     }
 
     fn selected_button_style(&self) -> Style {
-        Style::default().fg(Color::White).bg(Color::Yellow)
+        Style::default().fg(Color::Black).bg(Color::Yellow)
     }
+
     fn progressbar_style(&self) -> Style {
-        Style::default().fg(Color::Yellow).bg(Color::LightYellow)
+        Style::default().fg(Color::Blue).bg(Color::Gray)
+    }
+
+    fn diff_added_style(&self) -> Style {
+        Style::default().fg(Color::Green)
+    }
+
+    fn diff_removed_style(&self) -> Style {
+        Style::default().fg(Color::Red)
     }
 }
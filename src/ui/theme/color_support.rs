@@ -0,0 +1,164 @@
+use ratatui::style::{Color, Style};
+use std::env;
+
+const ANSI: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (128, 0, 0)),
+    (Color::Green, (0, 128, 0)),
+    (Color::Yellow, (128, 128, 0)),
+    (Color::Blue, (0, 0, 128)),
+    (Color::Magenta, (128, 0, 128)),
+    (Color::Cyan, (0, 128, 128)),
+    (Color::Gray, (192, 192, 192)),
+    (Color::DarkGray, (128, 128, 128)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (0, 0, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The colors the terminal can display, detected from `COLORTERM` and `TERM`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorSupport {
+    Ansi16,
+    Ansi256,
+    #[default]
+    TrueColor,
+}
+
+impl ColorSupport {
+    pub fn detect() -> Self {
+        Self::from_env(
+            &env::var("COLORTERM").unwrap_or_default(),
+            &env::var("TERM").unwrap_or_default(),
+        )
+    }
+
+    fn from_env(colorterm: &str, term: &str) -> Self {
+        if matches!(colorterm, "truecolor" | "24bit") {
+            ColorSupport::TrueColor
+        } else if term.contains("256") {
+            ColorSupport::Ansi256
+        } else {
+            ColorSupport::Ansi16
+        }
+    }
+
+    /// Replaces the colors of the style that the terminal cannot display with the nearest ones it can.
+    pub fn adapt(&self, style: Style) -> Style {
+        Style {
+            fg: style.fg.map(|c| self.color(c)),
+            bg: style.bg.map(|c| self.color(c)),
+            underline_color: style.underline_color.map(|c| self.color(c)),
+            ..style
+        }
+    }
+
+    fn color(&self, color: Color) -> Color {
+        match (self, color) {
+            (ColorSupport::TrueColor, _) => color,
+            (ColorSupport::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(Self::to_256(r, g, b)),
+            (ColorSupport::Ansi16, Color::Rgb(r, g, b)) => Self::to_16(r, g, b),
+            (ColorSupport::Ansi16, Color::Indexed(i)) => {
+                let (r, g, b) = Self::from_256(i);
+                Self::to_16(r, g, b)
+            }
+            _ => color,
+        }
+    }
+
+    fn to_16(r: u8, g: u8, b: u8) -> Color {
+        ANSI.iter()
+            .min_by_key(|(_, rgb)| Self::distance((r, g, b), *rgb))
+            .map(|(c, _)| *c)
+            .unwrap_or(Color::Reset)
+    }
+
+    /// The nearest color of the 6x6x6 cube or the gray ramp of the 256 color palette.
+    fn to_256(r: u8, g: u8, b: u8) -> u8 {
+        let level = |c: u8| {
+            CUBE.iter()
+                .enumerate()
+                .min_by_key(|(_, v)| (**v as i32 - c as i32).abs())
+                .map(|(i, _)| i as u8)
+                .unwrap_or_default()
+        };
+        let cube = 16 + 36 * level(r) + 6 * level(g) + level(b);
+        let gray =
+            232 + (((r as u16 + g as u16 + b as u16) / 3).saturating_sub(3) / 10).min(23) as u8;
+
+        [cube, gray]
+            .into_iter()
+            .min_by_key(|i| Self::distance((r, g, b), Self::from_256(*i)))
+            .unwrap_or(cube)
+    }
+
+    fn from_256(i: u8) -> (u8, u8, u8) {
+        match i {
+            0..=15 => ANSI[i as usize].1,
+            16..=231 => {
+                let i = i - 16;
+                (
+                    CUBE[(i / 36) as usize],
+                    CUBE[(i / 6 % 6) as usize],
+                    CUBE[(i % 6) as usize],
+                )
+            }
+            _ => {
+                let v = 8 + (i - 232) * 10;
+                (v, v, v)
+            }
+        }
+    }
+
+    fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+        [(r1, r2), (g1, g2), (b1, b2)]
+            .into_iter()
+            .map(|(a, b)| (a as i32 - b as i32).pow(2) as u32)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("truecolor", "xterm-256color", ColorSupport::TrueColor)]
+    #[case("24bit", "", ColorSupport::TrueColor)]
+    #[case("", "xterm-256color", ColorSupport::Ansi256)]
+    #[case("yes", "screen-256color", ColorSupport::Ansi256)]
+    #[case("", "xterm", ColorSupport::Ansi16)]
+    #[case("", "", ColorSupport::Ansi16)]
+    fn test_from_env(#[case] colorterm: &str, #[case] term: &str, #[case] expected: ColorSupport) {
+        assert_eq!(ColorSupport::from_env(colorterm, term), expected);
+    }
+
+    #[rstest]
+    #[case(
+        ColorSupport::TrueColor,
+        Color::Rgb(215, 95, 0),
+        Color::Rgb(215, 95, 0)
+    )]
+    #[case(ColorSupport::Ansi256, Color::Rgb(215, 95, 0), Color::Indexed(166))]
+    #[case(ColorSupport::Ansi256, Color::Rgb(255, 0, 0), Color::Indexed(196))]
+    #[case(ColorSupport::Ansi256, Color::Rgb(128, 128, 128), Color::Indexed(244))]
+    #[case(ColorSupport::Ansi256, Color::Indexed(166), Color::Indexed(166))]
+    #[case(ColorSupport::Ansi256, Color::Red, Color::Red)]
+    #[case(ColorSupport::Ansi16, Color::Indexed(196), Color::LightRed)]
+    #[case(ColorSupport::Ansi16, Color::Indexed(21), Color::LightBlue)]
+    #[case(ColorSupport::Ansi16, Color::Indexed(244), Color::DarkGray)]
+    #[case(ColorSupport::Ansi16, Color::Rgb(0, 0, 130), Color::Blue)]
+    #[case(ColorSupport::Ansi16, Color::Green, Color::Green)]
+    fn test_adapt(#[case] support: ColorSupport, #[case] color: Color, #[case] expected: Color) {
+        assert_eq!(
+            support.adapt(Style::default().fg(color).bg(color)),
+            Style::default().fg(expected).bg(expected)
+        );
+    }
+}
@@ -0,0 +1,231 @@
+use super::{ColorSupport, Dark, Light, Theme};
+use crate::models::config::config_dir;
+use anyhow::{anyhow, Result};
+use ratatui::style::{Color, Modifier, Style};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// A style in a theme file, either a foreground color or a table of colors and modifiers.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+enum StyleDef {
+    Color(String),
+    Style {
+        fg: Option<String>,
+        bg: Option<String>,
+        #[serde(default)]
+        modifiers: Vec<String>,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct ThemeFile {
+    base: Option<String>,
+    #[serde(flatten)]
+    styles: BTreeMap<String, StyleDef>,
+}
+
+macro_rules! styles {
+    ($($name:ident => $method:ident),* $(,)?) => {
+        /// A theme with every style resolved, built from a base theme, the styles of a theme file
+        /// and the colors the terminal can display.
+        #[derive(Clone, Debug)]
+        pub struct CustomTheme {
+            support: ColorSupport,
+            $($name: Style,)*
+        }
+
+        impl CustomTheme {
+            pub fn new(base: &dyn Theme, support: ColorSupport) -> Self {
+                Self {
+                    support,
+                    $($name: support.adapt(base.$method()),)*
+                }
+            }
+
+            fn set(&mut self, name: &str, style: Style) -> Result<()> {
+                $(if name == stringify!($name).trim_start_matches("r#") {
+                    self.$name = self.support.adapt(style);
+                    return Ok(());
+                })*
+
+                Err(anyhow!("Unknown style: {}", name))
+            }
+        }
+
+        impl Theme for CustomTheme {
+            $(fn $method(&self) -> Style {
+                self.$name
+            })*
+
+            fn adapt(&self, style: Style) -> Style {
+                self.support.adapt(style)
+            }
+        }
+    };
+}
+
+styles! {
+    foreground => foreground_style,
+    file => file_style,
+    line => line_style,
+    line_no => line_no_style,
+    border => border_style,
+    disabled => disabled_style,
+    highlight => highlight_style,
+    popup => popup_style,
+    status => status_style,
+    info => info_style,
+    error => error_style,
+    warn => warn_style,
+    count => count_style,
+    r#match => match_style,
+    filter => filter_style,
+    transform => transform_style,
+    button => button_style,
+    selected_button => selected_button_style,
+    progressbar => progressbar_style,
+    diff_added => diff_added_style,
+    diff_removed => diff_removed_style,
+}
+
+impl CustomTheme {
+    /// `dark`, `light`, or a theme file given by its path or by its name in `~/.config/igr/themes`.
+    pub fn load(name: &str, support: ColorSupport) -> Result<Self> {
+        match name {
+            "dark" => Ok(Self::new(&Dark, support)),
+            "light" => Ok(Self::new(&Light, support)),
+            _ => Self::read(&Self::path(name)?, support),
+        }
+    }
+
+    fn path(name: &str) -> Result<PathBuf> {
+        if name.ends_with(".toml") || name.contains(std::path::MAIN_SEPARATOR) {
+            Ok(PathBuf::from(name))
+        } else {
+            config_dir()
+                .map(|dir| dir.join("themes").join(format!("{}.toml", name)))
+                .filter(|path| path.is_file())
+                .ok_or_else(|| anyhow!("Unknown theme: {}", name))
+        }
+    }
+
+    fn read(path: &Path, support: ColorSupport) -> Result<Self> {
+        let error = |e: String| anyhow!("Invalid theme file {}: {}", path.display(), e);
+        let file: ThemeFile =
+            toml::from_str(&fs::read_to_string(path)?).map_err(|e| error(e.to_string()))?;
+        let mut theme = match file.base.as_deref() {
+            Some("light") => Self::new(&Light, support),
+            Some("dark") | None => Self::new(&Dark, support),
+            Some(base) => return Err(error(format!("unknown base theme `{}`", base))),
+        };
+
+        for (name, style) in file.styles {
+            theme
+                .set(&name, Self::style(style).map_err(&error)?)
+                .map_err(|e| error(e.to_string()))?;
+        }
+
+        Ok(theme)
+    }
+
+    fn style(def: StyleDef) -> Result<Style, String> {
+        let color = |s: &str| Color::from_str(s).map_err(|_| format!("invalid color `{}`", s));
+
+        match def {
+            StyleDef::Color(fg) => Ok(Style::default().fg(color(&fg)?)),
+            StyleDef::Style { fg, bg, modifiers } => {
+                let mut style = Style::default();
+
+                if let Some(fg) = fg {
+                    style = style.fg(color(&fg)?);
+                }
+
+                if let Some(bg) = bg {
+                    style = style.bg(color(&bg)?);
+                }
+
+                modifiers.iter().try_fold(style, |style, m| {
+                    Modifier::from_name(&m.to_uppercase())
+                        .map(|m| style.add_modifier(m))
+                        .ok_or_else(|| format!("invalid modifier `{}`", m))
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use std::hash::{DefaultHasher, Hash, Hasher};
+
+    #[rstest]
+    #[case(StyleDef::Color("light-blue".to_string()), Ok(Style::default().fg(Color::LightBlue)))]
+    #[case(StyleDef::Color("#d75f00".to_string()), Ok(Style::default().fg(Color::Rgb(215, 95, 0))))]
+    #[case(StyleDef::Color("166".to_string()), Ok(Style::default().fg(Color::Indexed(166))))]
+    #[case(StyleDef::Color("nope".to_string()), Err("invalid color `nope`".to_string()))]
+    #[case(StyleDef::Style { fg: Some("red".to_string()), bg: Some("#000000".to_string()), modifiers: vec!["bold".to_string(), "Italic".to_string()] },
+           Ok(Style::default().fg(Color::Red).bg(Color::Rgb(0, 0, 0)).add_modifier(Modifier::BOLD | Modifier::ITALIC)))]
+    #[case(StyleDef::Style { fg: None, bg: Some("nope".to_string()), modifiers: vec![] }, Err("invalid color `nope`".to_string()))]
+    #[case(StyleDef::Style { fg: None, bg: None, modifiers: vec!["shiny".to_string()] }, Err("invalid modifier `shiny`".to_string()))]
+    fn test_style(#[case] def: StyleDef, #[case] expected: Result<Style, String>) {
+        assert_eq!(CustomTheme::style(def), expected);
+    }
+
+    #[rstest]
+    #[case(
+        "match = \"#d75f00\"\n",
+        ColorSupport::TrueColor,
+        Ok(Color::Rgb(215, 95, 0))
+    )]
+    #[case(
+        "match = \"#d75f00\"\n",
+        ColorSupport::Ansi256,
+        Ok(Color::Indexed(166))
+    )]
+    #[case("match = \"196\"\n", ColorSupport::Ansi16, Ok(Color::LightRed))]
+    #[case(
+        "match = \"nope\"\n",
+        ColorSupport::TrueColor,
+        Err("invalid color `nope`")
+    )]
+    #[case(
+        "base = \"solarized\"\n",
+        ColorSupport::TrueColor,
+        Err("unknown base theme `solarized`")
+    )]
+    #[case(
+        "matches = \"red\"\n",
+        ColorSupport::TrueColor,
+        Err("Unknown style: matches")
+    )]
+    fn test_read(
+        #[case] text: &str,
+        #[case] support: ColorSupport,
+        #[case] expected: Result<Color, &str>,
+    ) {
+        let mut hasher = DefaultHasher::new();
+        (text, support as u8).hash(&mut hasher);
+        let path = std::env::temp_dir().join(format!(
+            "igr-theme-{}-{:x}.toml",
+            std::process::id(),
+            hasher.finish()
+        ));
+        fs::write(&path, text).unwrap();
+        let theme = CustomTheme::read(&path, support);
+        fs::remove_file(&path).unwrap();
+
+        match expected {
+            Ok(color) => assert_eq!(theme.unwrap().match_style().fg, Some(color)),
+            Err(message) => assert_eq!(
+                theme.unwrap_err().to_string(),
+                format!("Invalid theme file {}: {}", path.display(), message)
+            ),
+        }
+    }
+}
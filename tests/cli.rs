@@ -0,0 +1,36 @@
+use std::fs;
+use std::path::Path;
+use std::process::{self, Command, Output, Stdio};
+
+/// Runs igr in `dir`, with the config and state directories inside it.
+fn igr(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_igr"))
+        .args(args)
+        .current_dir(dir)
+        .env("HOME", dir)
+        .env("XDG_CONFIG_HOME", dir.join("config"))
+        .env("XDG_DATA_HOME", dir.join("data"))
+        .env("XDG_STATE_HOME", dir.join("state"))
+        .stdin(Stdio::null())
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_quiet_replace() {
+    let dir = std::env::temp_dir().join(format!("igr-cli-replace-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("a.txt"), "foo\n").unwrap();
+    fs::write(dir.join("b.txt"), "foo bar\n").unwrap();
+
+    let output = igr(&dir, &["--disable-tui", "-q", "-r", "foo | upper_case()"]);
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(fs::read_to_string(dir.join("a.txt")).unwrap(), "FOO\n");
+    assert_eq!(fs::read_to_string(dir.join("b.txt")).unwrap(), "FOO bar\n");
+    fs::remove_dir_all(&dir).unwrap();
+}